@@ -0,0 +1,206 @@
+use super::*;
+
+impl<P: Player, A: Action> Node<P,A> {
+    fn sibling(&self) -> bool {
+        match *self {
+            Node::Unknown(s,_) |
+            Node::Terminal(s,..) |
+            Node::Leaf(s,..) |
+            Node::Branch(s,..) |
+            Node::Transpose(s,..) => s,
+        }
+    }
+
+    fn action(&self) -> A {
+        match *self {
+            Node::Unknown(_,a) |
+            Node::Terminal(_,a,..) |
+            Node::Leaf(_,a,..) |
+            Node::Branch(_,a,..) |
+            Node::Transpose(_,a,..) => a,
+        }
+    }
+
+    fn with_sibling(self, s: bool) -> Self {
+        match self {
+            Node::Unknown(_,a) => Node::Unknown(s,a),
+            Node::Terminal(_,a,p,w,n) => Node::Terminal(s,a,p,w,n),
+            Node::Leaf(_,a,p,w,n) => Node::Leaf(s,a,p,w,n),
+            Node::Branch(_,a,p,w,n,c) => Node::Branch(s,a,p,w,n,c),
+            Node::Transpose(_,a,u,w,n) => Node::Transpose(s,a,u,w,n),
+        }
+    }
+}
+
+impl<P: Player, A: Action + PartialEq, S: GameState<P,A>> MCTS<P, A, S> {
+    ///Play the given action from the root game state and keep the part of the search tree that is reachable from the next game state. This saves searching the same game states again on the next turn (e.g. in an engine that plays a whole game). Returns false when nothing could be kept and the search starts over from the next game state.
+    ///
+    ///Values in the tree are relative to the side to play at the root when draws are not valued evenly (see with_contempt), so the tree is also discarded when the side to play changes in that case.
+    pub fn advance(&mut self, action: A) -> bool {
+        let mut next = self.root.clone();
+        next.apply(action);
+
+        let contempt = (self.draw != 0.5) && (next.player() != self.root.player());
+        match self.child(action) {
+            Some(u) if !contempt && matches!(self.stack[u],Node::Branch(..)) => {
+                self.reroot(u,next);
+                true
+            },
+            _ => {
+                self.reset(next);
+                false
+            }
+        }
+    }
+
+    //Index of the node reached by the action from the root
+    fn child(&self, action: A) -> Option<usize> {
+        let action = self.root.transform(action,self.symmetry(&self.root));
+        let mut child = None;
+        self.children(&mut |u,a,_| {
+            if a == action {
+                child = Some(u);
+            }
+        });
+
+        match self.stack[child?] {
+            Node::Transpose(_,_,u,_,_) => Some(u),
+            _ => child,
+        }
+    }
+}
+
+impl<P: Player, A: Action, S: GameState<P,A>> MCTS<P, A, S> {
+    //Discards the search tree and starts over from the given game state.
+    pub(crate) fn reset(&mut self, root: S) {
+        self.root = root;
+        self.stack.clear();
+        self.margins.clear();
+        self.map.clear();
+        self.states.clear();
+        self.mast.clear();
+
+        let info = &mut self.info;
+        info.q = 0.0;
+        info.n = 0;
+        info.branch = 0;
+        info.leaf = 0;
+        info.terminal = 0;
+        info.unknown = 0;
+        info.transpose = 0;
+        info.lookups = 0;
+        info.collisions = 0;
+        info.hit_rate = 0.0;
+        info.nodes.clear();
+    }
+
+    //Copies the subtree below node u into a new stack with u as the root. Children of a branch are copied together because they must be contiguous. Nodes that are only reachable through a transposition are copied on their own after everything else, and they are discovered again as a transposition if they are reached from a branch later.
+    fn reroot(&mut self, u: usize, root: S) {
+        let old = std::mem::take(&mut self.stack);
+        let margins = std::mem::take(&mut self.margins);
+        let mut index = HashMap::<usize,usize>::default();
+        let mut queue = vec![(u,0)];
+        let mut pending = Vec::new();
+        let mut nodes = vec![1];
+
+        self.stack.push(old[u].with_sibling(false));
+        self.margins.push(margins[u]);
+        index.insert(u,0);
+
+        loop {
+            while let Some((v,depth)) = queue.pop() {
+                let i = index[&v];
+                match self.stack[i] {
+                    Node::Branch(s,a,p,w,n,c) => {
+                        let start = self.stack.len();
+                        let mut sibling = Some(c);
+                        while let Some(x) = sibling {
+                            let s = old[x].sibling();
+                            let y = self.stack.len();
+                            if let std::collections::hash_map::Entry::Vacant(e) = index.entry(x) {
+                                e.insert(y);
+                                self.stack.push(old[x]);
+                                self.margins.push(margins[x]);
+                                queue.push((x,depth + 1));
+                            } else {
+                                //Already copied on its own, so it is found again as a transposition
+                                self.stack.push(Node::Unknown(s,old[x].action()));
+                                self.margins.push(0.0);
+                            }
+                            sibling = s.then(||x + 1);
+                        }
+
+                        if nodes.len() <= depth + 1 {
+                            nodes.resize(depth + 2,0);
+                        }
+                        nodes[depth + 1] += (self.stack.len() - start) as u32;
+                        self.stack[i] = Node::Branch(s,a,p,w,n,start);
+                    },
+                    Node::Transpose(_,_,x,_,_) => pending.push((x,depth)),
+                    _ => {},
+                }
+            }
+
+            match pending.pop() {
+                Some((x,_)) if index.contains_key(&x) => {},
+                Some((x,depth)) => {
+                    self.stack.push(old[x].with_sibling(false));
+                    self.margins.push(margins[x]);
+                    index.insert(x,self.stack.len() - 1);
+                    queue.push((x,depth));
+                },
+                None => break,
+            }
+        }
+
+        let info = &mut self.info;
+        info.branch = 0;
+        info.leaf = 0;
+        info.terminal = 0;
+        info.unknown = 0;
+        info.transpose = 0;
+        info.collisions = 0;
+        for node in self.stack.iter_mut() {
+            match *node {
+                Node::Unknown(..) => info.unknown += 1,
+                Node::Terminal(..) => info.terminal += 1,
+                Node::Leaf(..) => info.leaf += 1,
+                Node::Branch(..) => info.branch += 1,
+                Node::Transpose(s,a,x,w,n) => {
+                    *node = Node::Transpose(s,a,index[&x],w,n);
+                    info.transpose += 1;
+                },
+            }
+        }
+        info.nodes = nodes;
+        //Every node below the root was discovered by one lookup, so the hit rate is the same as for a new tree with these nodes
+        info.lookups = if self.use_transposition {info.branch + info.leaf + info.terminal + info.transpose - 1} else {0};
+        info.hit_rate = if info.lookups > 0 {(info.transpose as f32)/(info.lookups as f32)} else {0.0};
+        if let Node::Branch(_,_,_,w,n,_) = self.stack[0] {
+            info.q = w/(n as f32);
+            info.n = n;
+        }
+
+        //The side to play at the root is part of the keys of the transposition map and the MAST averages
+        let flip = root.player() != self.root.player();
+        let map = std::mem::take(&mut self.map);
+        for ((h,side),(x,t)) in map {
+            if let Some(&y) = index.get(&x) {
+                self.map.insert((h,side != flip),(y,t));
+            }
+        }
+        if flip {
+            let mast = std::mem::take(&mut self.mast);
+            self.mast = mast.into_iter().map(|((side,k),v)| ((!side,k),v)).collect();
+        }
+
+        let states = std::mem::take(&mut self.states);
+        for (x,state) in states {
+            if let Some(&y) = index.get(&x) {
+                self.states.insert(y,state);
+            }
+        }
+
+        self.root = root;
+    }
+}
@@ -0,0 +1,308 @@
+use super::*;
+use std::io::BufRead;
+use std::io::Write;
+use std::marker::PhantomData;
+use rand::SeedableRng;
+use rand::Rng as _;
+
+///This trait describes a player that chooses actions for a game state (e.g. a search, a random player, or a human). Use "play" to pit two agents against each other.
+pub trait Agent<P: Player, A: Action, S: GameState<P,A>> {
+    ///Choose an action for the given game state. The game state is never in a game over condition. Return None to resign the game.
+    fn choose(&mut self, state: &S) -> Option<A>;
+}
+
+///This enum describes how much searching an MCTS agent does before choosing an action.
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum Budget {
+    ///Search the given number of iterations and play the best action.
+    Iterations(usize),
+    ///Search for the given duration and play the best action.
+    Time(std::time::Duration),
+    ///Search and sample an action like the given strength (see MCTS::play_like).
+    Strength(Strength),
+}
+
+///This struct is an agent that searches each game state with MCTS. The configure function builds the search for each game state, so any of the MCTS builder options can be used (e.g. |state| MCTS::new(state).with_transposition()).
+pub struct MctsAgent<P: Player, A: Action, S: GameState<P,A>, F: FnMut(S) -> MCTS<P,A,S>> {
+    configure: F,
+    budget: Budget,
+    ///The metrics of the last search. For informational purposes only.
+    pub info: Info,
+    game: PhantomData<(P,A,S)>,
+}
+
+impl<P: Player, A: Action, S: GameState<P,A>, F: FnMut(S) -> MCTS<P,A,S>> MctsAgent<P,A,S,F> {
+    ///Create an agent that searches each game state built by the configure function with the given budget.
+    pub fn new(configure: F, budget: Budget) -> Self {
+        MctsAgent {
+            configure,
+            budget,
+            info: Info::default(),
+            game: PhantomData,
+        }
+    }
+}
+
+impl<P: Player, A: Action, S: GameState<P,A>, F: FnMut(S) -> MCTS<P,A,S>> Agent<P,A,S> for MctsAgent<P,A,S,F> {
+    fn choose(&mut self, state: &S) -> Option<A> {
+        let mut mcts = (self.configure)(state.clone());
+        let action = match self.budget {
+            Budget::Iterations(n) => {
+                mcts.ponder(n);
+                mcts.best()
+            },
+            Budget::Time(duration) => {
+                let start = instant::Instant::now();
+                while start.elapsed() < duration {
+                    mcts.ponder(100);
+                }
+                mcts.best()
+            },
+            Budget::Strength(strength) => mcts.play_like(strength),
+        };
+        self.info = mcts.info;
+        action
+    }
+}
+
+///This struct is an agent that plays uniformly random actions.
+pub struct RandomAgent {
+    rand: Rng,
+}
+
+impl RandomAgent {
+    ///Create a random agent with the given seed. Agents with the same seed play the same actions.
+    pub fn new(seed: u64) -> Self {
+        RandomAgent {rand: Rng::seed_from_u64(seed)}
+    }
+}
+
+impl<P: Player, A: Action, S: GameState<P,A>> Agent<P,A,S> for RandomAgent {
+    fn choose(&mut self, state: &S) -> Option<A> {
+        let mut actions = Vec::new();
+        state.actions(&mut |a| actions.push(a));
+        if actions.is_empty() {
+            None
+        } else {
+            Some(actions[self.rand.gen_range(0..actions.len())])
+        }
+    }
+}
+
+///This struct is an agent that searches a fixed number of actions ahead with alpha-beta pruning. Game states at the search horizon are valued with the custom evaluation method of the game state (see GameState::custom_evaluation). A depth of one greedily plays the action with the best evaluation.
+pub struct AlphaBetaAgent {
+    depth: u32,
+    ///Number of game states visited by the last search. For informational purposes only.
+    pub nodes: u64,
+}
+
+impl AlphaBetaAgent {
+    ///Create an agent that searches the given number of actions ahead. The depth must be at least one.
+    pub fn new(depth: u32) -> Self {
+        assert!(depth > 0,"The search depth must be greater than zero.");
+        AlphaBetaAgent {depth, nodes: 0}
+    }
+
+    ///Create an agent that plays the action with the best evaluation.
+    pub fn greedy() -> Self {
+        Self::new(1)
+    }
+
+    //Returns the win probability for the player of the game state.
+    fn search<P: Player, A: Action, S: GameState<P,A>>(&mut self, state: &S, depth: u32, mut alpha: f32, beta: f32) -> f32 {
+        self.nodes += 1;
+        match state.gameover() {
+            Some(GameResult::Win) => return 1.0,
+            Some(GameResult::Lose) => return 0.0,
+            Some(GameResult::Draw) => return 0.5,
+            None if depth == 0 => return state.custom_evaluation(),
+            None => {},
+        }
+
+        let mut actions = Vec::new();
+        state.actions(&mut |a| actions.push(a));
+
+        let mut best = 0.0;
+        for a in actions {
+            let v = self.value(state,a,depth,alpha,beta);
+            best = f32::max(best,v);
+            alpha = f32::max(alpha,v);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+
+    //Returns the win probability of the action for the player of the game state. Some games allow a player to take more than one action in a row, so the window is only flipped when the side to play changes.
+    fn value<P: Player, A: Action, S: GameState<P,A>>(&mut self, state: &S, action: A, depth: u32, alpha: f32, beta: f32) -> f32 {
+        let next = state.make(action);
+        if next.player() == state.player() {
+            self.search(&next,depth - 1,alpha,beta)
+        } else {
+            1.0 - self.search(&next,depth - 1,1.0 - beta,1.0 - alpha)
+        }
+    }
+}
+
+impl<P: Player, A: Action, S: GameState<P,A>> Agent<P,A,S> for AlphaBetaAgent {
+    fn choose(&mut self, state: &S) -> Option<A> {
+        let mut actions = Vec::new();
+        state.actions(&mut |a| actions.push(a));
+        self.nodes = 0;
+
+        let mut best = None;
+        let mut alpha = -1.0;
+        for a in actions {
+            let v = self.value(state,a,self.depth,alpha,1.0);
+            if v > alpha {
+                alpha = v;
+                best = Some(a);
+            }
+        }
+        best
+    }
+}
+
+///Marker for a human agent that identifies actions by their index in the order they are listed by GameState::actions (see HumanAgent).
+#[derive(Debug,Copy,Clone,Default)]
+pub struct Ordinal;
+
+///Marker for a human agent that identifies actions by their notation (see HumanAgent::with_notation).
+#[derive(Debug,Copy,Clone,Default)]
+pub struct Written;
+
+///This struct is an agent that asks a person to choose an action. The game state and the legal actions are printed to the output, and an action is read from the input. Entering "quit" resigns the game.
+///
+///Actions are identified by their ordinal index by default, so any game state can be played. Call with_notation to identify actions by the notation of the game instead (see Notation).
+pub struct HumanAgent<R: BufRead, W: Write, N = Ordinal> {
+    input: R,
+    output: W,
+    mode: PhantomData<N>,
+}
+
+impl<R: BufRead, W: Write> HumanAgent<R,W> {
+    ///Create an agent that reads actions from the input and prints prompts to the output.
+    pub fn new(input: R, output: W) -> Self {
+        HumanAgent {input, output, mode: PhantomData}
+    }
+
+    ///Read and print actions in the notation of the game instead of by index. Entering "actions" prints the legal actions again.
+    pub fn with_notation(self) -> HumanAgent<R,W,Written> {
+        HumanAgent {input: self.input, output: self.output, mode: PhantomData}
+    }
+}
+
+impl HumanAgent<std::io::StdinLock<'static>,std::io::Stdout> {
+    ///Create an agent that plays through standard input and output.
+    pub fn stdio() -> Self {
+        Self::new(std::io::stdin().lock(),std::io::stdout())
+    }
+}
+
+impl<R: BufRead, W: Write, N> HumanAgent<R,W,N> {
+    //Prompts for a line of input. Returns None when the input is closed or the person resigns.
+    fn line(&mut self) -> Option<String> {
+        write!(self.output,"=> ").ok()?;
+        //flushes the output so the prompt is actually displayed
+        self.output.flush().ok()?;
+
+        let mut line = String::new();
+        if self.input.read_line(&mut line).ok()? == 0 {
+            //The input was closed
+            return None;
+        }
+
+        match line.trim() {
+            "quit" => None,
+            line => Some(String::from(line)),
+        }
+    }
+}
+
+impl<P: Player, A: Action, S: GameState<P,A>, R: BufRead, W: Write> Agent<P,A,S> for HumanAgent<R,W,Ordinal> {
+    fn choose(&mut self, state: &S) -> Option<A> {
+        let mut actions = Vec::new();
+        state.actions(&mut |a| actions.push(a));
+
+        writeln!(self.output,"{}",state).ok()?;
+        for (i,a) in actions.iter().enumerate() {
+            writeln!(self.output,"{}: {:?}",i,a).ok()?;
+        }
+
+        loop {
+            match self.line()?.parse::<usize>() {
+                Ok(i) if i < actions.len() => return Some(actions[i]),
+                Ok(_) => writeln!(self.output,"validation failed").ok()?,
+                Err(_) => writeln!(self.output,"parse failed").ok()?,
+            }
+        }
+    }
+}
+
+impl<P: Player, A: Action, S: Notation<P,A>, R: BufRead, W: Write> Agent<P,A,S> for HumanAgent<R,W,Written> {
+    fn choose(&mut self, state: &S) -> Option<A> {
+        writeln!(self.output,"{}",state).ok()?;
+        writeln!(self.output,"{}",state.legal().join(" ")).ok()?;
+
+        loop {
+            let line = self.line()?;
+            match line.as_str() {
+                "actions" => writeln!(self.output,"{}",state.legal().join(" ")).ok()?,
+                text => match state.parse(text) {
+                    Some(action) => return Some(action),
+                    None => writeln!(self.output,"illegal action {}, enter \"actions\" to list the legal actions or \"quit\" to resign",text).ok()?,
+                }
+            }
+        }
+    }
+}
+
+///This struct describes a game played between two agents (see play).
+#[derive(Debug,Clone)]
+pub struct Outcome<A: Action, S> {
+    ///The actions played in order from the initial game state.
+    pub actions: Vec<A>,
+    ///The final game state.
+    pub state: S,
+    ///The result of the game from the perspective of the first agent. A resignation is a loss for the agent that resigned.
+    pub result: GameResult,
+    ///Indicates that the game ended by resignation rather than a game over condition.
+    pub resigned: bool,
+}
+
+///Play a game between two agents from the given game state until it is over. The first agent plays for the side to play in the given game state and the second agent plays for the other side. The callback f is called with each game state and the action chosen for it (e.g. to print the game as it is played).
+pub fn play<P,A,S,X,Y,F>(state: S, first: &mut X, second: &mut Y, f: &mut F) -> Outcome<A,S>
+where P: Player, A: Action, S: GameState<P,A>, X: Agent<P,A,S> + ?Sized, Y: Agent<P,A,S> + ?Sized, F: FnMut(&S,A) {
+    let side = state.player();
+    let mut state = state;
+    let mut actions = Vec::new();
+
+    loop {
+        if let Some(result) = state.gameover() {
+            let result = if state.player() == side {
+                result
+            } else {
+                match result {
+                    GameResult::Win => GameResult::Lose,
+                    GameResult::Lose => GameResult::Win,
+                    GameResult::Draw => GameResult::Draw,
+                }
+            };
+            return Outcome {actions, state, result, resigned: false};
+        }
+
+        let choice = if state.player() == side {first.choose(&state)} else {second.choose(&state)};
+        match choice {
+            Some(action) => {
+                f(&state,action);
+                state.apply(action);
+                actions.push(action);
+            },
+            None => {
+                let result = if state.player() == side {GameResult::Lose} else {GameResult::Win};
+                return Outcome {actions, state, result, resigned: true};
+            }
+        }
+    }
+}
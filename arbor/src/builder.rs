@@ -17,22 +17,119 @@ impl<P: Player, A: Action, S: GameState<P,A>> MCTS<P, A, S> {
         self
     }
 
+    ///Sets the value of actions that have not been tried yet (see FirstPlayUrgency).
+    pub fn with_first_play_urgency(mut self, fpu: FirstPlayUrgency) -> Self {
+        match fpu {
+            FirstPlayUrgency::Value(v) => 
+                assert!((0.0..=1.0).contains(&v),"The first play value must be between zero and one."),
+            FirstPlayUrgency::Reduction(r) => 
+                assert!(r >= 0.0,"The first play reduction must not be negative."),
+            _ => {}
+        }
+        self.fpu = fpu;
+        self
+    }
+    
     ///Enables the custom evaluation method.
     pub fn with_custom_evaluation(mut self) -> Self {
         self.use_custom_evaluation = true;
         self
     }
     
-    ///Enables transposition detection. Experimental.
+    ///Sets the policy for choosing actions during random playouts.
+    pub fn with_rollout_policy(mut self, policy: RolloutPolicy) -> Self {
+        match policy {
+            RolloutPolicy::EpsilonGreedy(e) => 
+                assert!((0.0..=1.0).contains(&e),"Epsilon must be between zero and one."),
+            RolloutPolicy::Softmax(t) |
+            RolloutPolicy::Mast(t) => 
+                assert!(t > 0.0,"A positive value is required for the softmax temperature."),
+            _ => {}
+        }
+        self.policy = policy;
+        self
+    }
+    
+    ///Sets the lookahead used to find decisive actions in random playouts. This is disabled by default.
+    pub fn with_decisive_moves(mut self, decisive: DecisiveMoves) -> Self {
+        self.decisive = decisive;
+        self
+    }
+    
+    ///Sets the maximum number of actions in a random playout. When the limit is reached before the game is over, the final game state is scored with the custom evaluation method instead.
+    pub fn with_rollout_depth(mut self, depth: u32) -> Self {
+        self.depth = depth;
+        self
+    }
+    
+    ///Blends the value of random playouts with the custom evaluation method. Leaf nodes are valued at (1 - lambda)*evaluation + lambda*playout. Set to a value between 0 and 1. The default of 1 uses random playouts only.
+    pub fn with_evaluation_blend(mut self, lambda: f32) -> Self {
+        assert!((0.0..=1.0).contains(&lambda),"Lambda must be between zero and one.");
+        self.lambda = lambda;
+        self
+    }
+    
+    ///Blends the win probability with the normalized score margin of game over states (see GameState::score). Terminal values are (1 - weight)*result + weight*(margin + 1)/2. Set to a value between 0 and 1. The default of 0 only considers wins, losses, and draws. A small weight keeps the search playing for a larger margin once the game is decided.
+    pub fn with_score_weight(mut self, weight: f32) -> Self {
+        assert!((0.0..=1.0).contains(&weight),"The score weight must be between zero and one.");
+        self.score_weight = weight;
+        self
+    }
+    
+    ///Sets the value of a draw for the side to play at the root. The opponent values a draw at 1 - draw. Set to a value between 0 and 1. The default of 0.5 is neutral. Lower values avoid draws (e.g. against a weaker opponent) and higher values steer into them (e.g. as the underdog).
+    pub fn with_contempt(mut self, draw: f32) -> Self {
+        assert!((0.0..=1.0).contains(&draw),"The draw value must be between zero and one.");
+        self.draw = draw;
+        self
+    }
+    
+    ///Enables transposition detection. Game states with the same hash and side to play share a node in the search tree. The value of a shared node is combined with the visits of each action leading to it for selection (UCT3).
     pub fn with_transposition(mut self) -> Self {
         self.use_transposition = true;
         self
     }
     
+    ///Sets the maximum number of leaf nodes that are evaluated together by ponder_batch.
+    pub fn with_batch_size(mut self, batch: usize) -> Self {
+        assert!(batch > 0,"The batch size must be greater than zero.");
+        self.batch = batch;
+        self
+    }
+    
+    ///Enables transposition detection between symmetric game states using the "canonical", "transform", and "inverse" methods of the game state. Verification of game states is skipped when a transposition is found between different orientations.
+    pub fn with_symmetry(mut self) -> Self {
+        self.use_transposition = true;
+        self.use_symmetry = true;
+        self
+    }
+    
     ///Seeds the internal random number generator from entropy. This is inteneded to produce non-deterministic search results.
     pub fn with_entropy(mut self) -> Self {
         use rand::SeedableRng;
         self.rand = Rng::from_entropy();
         self
     }
-}
\ No newline at end of file
+    
+    ///Registers an observer that is called with a snapshot of the progress of the search each time the interval passes while pondering (e.g. to print the best action found so far). In batch mode the observer is only called between batches.
+    pub fn with_observer<F>(mut self, interval: Interval, f: F) -> Self where F: FnMut(&Snapshot<A>) + Send + 'static {
+        match interval {
+            Interval::Iterations(n) |
+            Interval::Millis(n) => 
+                assert!(n > 0,"The observer interval must be greater than zero."),
+        }
+        self.observer = Some((interval,Box::new(f)));
+        self.observed = (self.elapsed,self.iterations);
+        self
+    }
+}
+
+impl<P: Player, A: Action, S: GameState<P,A> + PartialEq> MCTS<P, A, S> {
+    
+    ///Enables transposition detection and verifies that game states with the same hash are equal before sharing a node. This guards against hash collisions at the cost of storing a copy of each game state in the transposition table.
+    pub fn with_verified_transposition(mut self) -> Self {
+        self.use_transposition = true;
+        self.verify = Some(S::eq);
+        self
+    }
+}
+
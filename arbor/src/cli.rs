@@ -0,0 +1,318 @@
+use super::*;
+use super::record::{side,winner};
+
+const USAGE: &str = "\
+usage:
+    <game>                      play against the computer as p1
+    <game> human p2             play against the computer as p2
+    <game> human p1 <file>      play against the computer and append the record of the game to a file
+    <game> replay <file>        replay and validate the records of games in a file
+    <game> engine               run a long lived engine on standard input (see arbor::engine)
+    <game> [commands ...]       run commands in order, where a command is one of
+        <action>                play the action written in notation
+        ordinal                 identify the actions of later commands by ordinal index instead of notation
+        show                    print the game state
+        side                    print the side to play (p1, p2, or none)
+        result                  print the winning side (p1, p2, draw, or none)
+        actions                 print the number of legal actions
+        legal                   print the legal actions in notation
+        record:<file>           append the record of the actions played so far to a file
+        perft:<depth>           print the number of leaves below each action to the depth and the total
+        perft:<depth>:cache     count the leaves with a cache of transpositions
+        conformance:<games>     check the game implementation over random games (see arbor::conformance)
+        mcts:iter:<n>           print the best action after n iterations
+        mcts:time:<ms>          print the best action after searching for ms milliseconds
+        mcts:clock:<ms>:<ms>    print the best action for the remaining time and increment
+        help                    print this message";
+
+fn ordinal<P: Player, A: Action + PartialEq, S: GameState<P,A>>(state: &S, action: A) -> usize {
+    let mut i = 0;
+    let mut index = 0;
+    state.actions(&mut |a| {
+        if a == action {
+            index = i;
+        }
+        i += 1;
+    });
+    index
+}
+
+//Estimates the number of actions left for the side to play from the average length of random playouts
+fn moves_left<P: Player, A: Action, S: GameState<P,A>>(state: &S) -> u32 {
+    const PLAYOUTS: u64 = 16;
+    let mut total = 0;
+    for seed in 0..PLAYOUTS {
+        let mut random = RandomAgent::new(seed);
+        let mut state = state.clone();
+        while state.gameover().is_none() {
+            match Agent::<P,A,S>::choose(&mut random,&state) {
+                Some(action) => state.apply(action),
+                None => break,
+            }
+            total += 1;
+        }
+    }
+    ((total/PLAYOUTS) as u32).div_ceil(2).max(1)
+}
+
+//Prints the progress of the search to standard error like a chess engine
+fn thinking<P: Player, A: Action, S: Notation<P,A>>(mcts: &MCTS<P,A,S>, state: &S, start: Instant) {
+    let info = &mcts.info;
+    eprintln!(
+        "info time {} visits {} depth {:.1}/{} rate {:.0} value {:.3} best {}",
+        start.elapsed().as_millis(),
+        info.n,
+        info.depth_avg,
+        info.depth_max,
+        info.rate,
+        info.q,
+        mcts.best().map_or(String::from("none"),|a| state.format(a)),
+    );
+}
+
+//Searches the game state for the given time and prints the progress every 100 ms
+fn search<P: Player, A: Action, S: Notation<P,A>>(mcts: &mut MCTS<P,A,S>, state: &S, duration: std::time::Duration) {
+    let start = Instant::now();
+    let mut last = start;
+    while start.elapsed() < duration {
+        mcts.ponder(100);
+        if last.elapsed().as_millis() >= 100 {
+            last = Instant::now();
+            thinking(mcts,state,start);
+        }
+    }
+}
+
+//Plays a game between a person and the computer
+fn interactive<P,A,S,F>(title: &str, start: S, configure: F, human: &str, file: Option<&String>)
+where P: Player, A: Action, S: Notation<P,A>, F: FnMut(S) -> MCTS<P,A,S> {
+    println!("{}!",title);
+
+    let p = start.player();
+    let mut mcts = MctsAgent::new(configure,Budget::Time(std::time::Duration::new(1,0)));
+    let mut f = |state: &S, action: A| {
+        println!("{} plays {}",side(p,state.player()),state.format(action));
+    };
+    let mut person = HumanAgent::stdio().with_notation();
+    let outcome = if human == "p2" {
+        play(start.clone(),&mut mcts,&mut person,&mut f)
+    } else {
+        play(start.clone(),&mut person,&mut mcts,&mut f)
+    };
+
+    if let Some(file) = file {
+        let person = Seat::new("human",serde_json::json!({"type": "human"}));
+        let computer = Seat::new("computer",serde_json::json!({"type": "mcts", "time": 1000}));
+        let (p1,p2) = if human == "p2" {(computer,person)} else {(person,computer)};
+        let record = Record::new(&game(title),p1,p2).with_actions(&start,&outcome.actions,outcome.resigned);
+        append(file,&record);
+    }
+
+    println!("{}",outcome.state);
+    if outcome.resigned {
+        println!("{} resigns",side(p,outcome.state.player()));
+    }
+    match winner(p,&outcome.state) {
+        "p1" => println!("p1 wins!"),
+        "p2" => println!("p2 wins!"),
+        "draw" => println!("Draw!"),
+        _ => {},
+    }
+}
+
+//Name of the game in records (e.g. "Connect 4" is "connect4")
+fn game(title: &str) -> String {
+    title.to_lowercase().replace(' ',"")
+}
+
+//Appends a record to the end of a file
+fn append(file: &str, record: &Record) {
+    let mut output = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(file)
+        .unwrap_or_else(|e| panic!("could not open {}: {}",file,e));
+    record.write(&mut output).unwrap_or_else(|e| panic!("could not write {}: {}",file,e));
+}
+
+//Replays the records of a file and prints a line for each one
+fn replay<P,A,S>(title: &str, start: S, file: &str)
+where P: Player, A: Action, S: Notation<P,A> {
+    let input = std::fs::File::open(file).unwrap_or_else(|e| panic!("could not open {}: {}",file,e));
+    let records = read_records(std::io::BufReader::new(input)).unwrap_or_else(|e| panic!("could not read {}: {}",file,e));
+    let mut valid = 0;
+    for (i,record) in records.iter().enumerate() {
+        let check = if record.game == game(title) {
+            record.replay(start.clone()).map(|_| ())
+        } else {
+            Err(format!("expected game {} but found {}",game(title),record.game))
+        };
+        match check {
+            Ok(()) => {
+                valid += 1;
+                println!(
+                    "{} {} {} vs {} {} actions {}{}",
+                    i + 1,
+                    record.date,
+                    record.p1.name,
+                    record.p2.name,
+                    record.moves.len(),
+                    record.result,
+                    if record.resigned {" by resignation"} else {""},
+                );
+            },
+            Err(e) => println!("{} error {}",i + 1,e),
+        }
+    }
+    println!("{} of {} records are valid",valid,records.len());
+}
+
+//Runs commands from the arguments in order
+fn script<P,A,S,F>(title: &str, start: S, configure: F, args: &[String])
+where P: Player + Sync, A: Action + PartialEq + Send + Sync, S: Notation<P,A> + PartialEq + Sync, F: FnMut(S) -> MCTS<P,A,S> {
+    let mut configure = configure;
+    let p = start.player();
+    let mut state = start.clone();
+    let mut actions = Vec::new();
+    let mut ordinals = false;
+
+    for arg in args {
+        let fields: Vec<&str> = arg.split(':').collect();
+        let number = |i: usize| -> u64 {
+            let s = fields.get(i).unwrap_or_else(|| panic!("missing number in {}",arg));
+            s.parse::<u64>().unwrap_or_else(|_| panic!("not an integer in {}",arg))
+        };
+
+        match fields[0] {
+            "help" => println!("{}",USAGE),
+            "ordinal" => ordinals = true,
+            "show" => println!("{}",state),
+            "side" => match state.gameover() {
+                Some(_) => println!("none"),
+                None => println!("{}",side(p,state.player())),
+            },
+            "result" => {
+                println!("{}",winner(p,&state));
+                if state.gameover().is_some() {
+                    return;
+                }
+            },
+            "actions" => {
+                let mut count = 0;
+                if state.gameover().is_none() {
+                    state.actions(&mut |_| count += 1);
+                }
+                println!("{}",count);
+            },
+            "legal" => println!("{}",state.legal().join(" ")),
+            "perft" => {
+                let depth = number(1) as u32;
+                let cache = fields.get(2) == Some(&"cache");
+                let start = Instant::now();
+                let mut total = 0;
+                for (action,n) in divide(&state,depth,cache) {
+                    println!("{} {}",state.format(action),n);
+                    total += n;
+                }
+                if depth == 0 || state.gameover().is_some() {
+                    total = 1;
+                }
+                println!("total {}",total);
+                eprintln!("time {} ms",start.elapsed().as_millis());
+            },
+            "conformance" => match conformance(&state,number(1) as usize,0) {
+                Ok(()) => println!("ok"),
+                Err(violation) => {
+                    let mut moves = Vec::new();
+                    let mut s = state.clone();
+                    for &a in violation.actions.iter() {
+                        moves.push(s.format(a));
+                        s.apply(a);
+                    }
+                    println!("{} after actions [{}]",violation.message,moves.join(" "));
+                },
+            },
+            "record" => {
+                let file = arg.split_once(':').map(|(_,file)| file).filter(|f| !f.is_empty());
+                let file = file.unwrap_or_else(|| panic!("missing file in {}",arg));
+                let seat = || Seat::new("script",serde_json::json!({"type": "script"}));
+                let record = Record::new(&game(title),seat(),seat()).with_actions(&start,&actions,false);
+                append(file,&record);
+            },
+            "mcts" => {
+                let mut mcts = configure(state.clone());
+                let action = match fields.get(1) {
+                    Some(&"iter") => {
+                        mcts.ponder(number(2) as usize);
+                        mcts.best()
+                    },
+                    Some(&"time") => {
+                        search(&mut mcts,&state,std::time::Duration::from_millis(number(2)));
+                        mcts.best()
+                    },
+                    Some(&"clock") => {
+                        let remaining = std::time::Duration::from_millis(number(2));
+                        let increment = std::time::Duration::from_millis(number(3));
+                        let moves = moves_left(&state);
+                        mcts.ponder_clock(Clock {remaining, increment, moves})
+                    },
+                    _ => panic!("unknown search {}",arg),
+                };
+                eprintln!("rust iterations {}",mcts.info.n);
+                let action = action.expect("Should find a best action");
+                if ordinals {
+                    println!("{}",ordinal(&state,action));
+                } else {
+                    println!("{}",state.format(action));
+                }
+            },
+            _ => {
+                if state.gameover().is_some() {
+                    panic!("error - game over");
+                }
+                //The notation of some games is also a number, so ordinal indices are only used when they are asked for
+                let action = if ordinals {
+                    arg.parse::<usize>().ok().and_then(|i| {
+                        let mut n = 0;
+                        let mut action = None;
+                        state.actions(&mut |a| {
+                            if n == i {
+                                action = Some(a);
+                            }
+                            n += 1;
+                        });
+                        action
+                    })
+                } else {
+                    state.parse(arg)
+                };
+                match action {
+                    Some(action) => {
+                        state.apply(action);
+                        actions.push(action);
+                    },
+                    //Options for other tools (e.g. "policy:rollout") are ignored
+                    None => eprintln!("ignoring argument {}",arg),
+                }
+            }
+        }
+    }
+}
+
+///Run a command line interface for a game with the arguments of the process. The title is printed when an interactive game begins, and the configure function builds the search for a game state (e.g. |state| MCTS::new(state).with_transposition()). Run with "help" to list the commands.
+///
+///Without arguments, a person plays the first side against the computer with actions written in notation. The record of the game can be saved to a file (see Record), and "replay" validates the records in a file. With "engine", the long lived engine protocol is run on standard input and output (see engine). Otherwise, the arguments are run in order as commands, where actions are written in notation. After the "ordinal" command, actions are identified by their ordinal index in the order they are listed by GameState::actions instead (as used by py/game.py).
+pub fn cli<P,A,S,F>(title: &str, start: S, configure: F)
+where P: Player + Sync, A: Action + PartialEq + Send + Sync, S: Notation<P,A> + PartialEq + Sync, F: FnMut(S) -> MCTS<P,A,S> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        None => interactive(title,start,configure,"p1",None),
+        Some("human") => interactive(title,start,configure,args.get(1).map_or("p1",String::as_str),args.get(2)),
+        Some("replay") => replay(title,start,args.get(1).expect("no file to replay")),
+        Some("engine") => {
+            let stdin = std::io::stdin();
+            engine(start,configure,stdin.lock(),&mut std::io::stdout()).expect("engine failed");
+        },
+        Some(_) => script(title,start,configure,&args),
+    }
+}
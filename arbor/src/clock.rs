@@ -0,0 +1,114 @@
+use super::*;
+use std::time::Duration;
+use instant::Instant;
+
+///This struct describes the game clock of the side to play.
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub struct Clock {
+    ///Time left on the clock.
+    pub remaining: Duration,
+    ///Time added to the clock after each move.
+    pub increment: Duration,
+    ///Estimated number of moves the side to play has left in the game (or until the next time control).
+    pub moves: u32,
+}
+
+///This struct decides how long to search for a move from the state of a game clock. The search stops at the soft limit unless the most visited action changed recently, and never runs past the hard limit. The search also stops early when the most visited action can not be overtaken before the soft limit.
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub struct TimeManager {
+    ///The target time for the move.
+    pub soft: Duration,
+    ///The most time the move may take.
+    pub hard: Duration,
+    leader: Option<usize>,
+    changed: Duration,
+}
+
+impl TimeManager {
+    ///Time kept in reserve for the overhead of making moves and communicating them.
+    pub const OVERHEAD: Duration = Duration::from_millis(50);
+
+    ///Computes the soft and hard limits for the next move. The soft limit is an even share of the remaining time plus most of the increment. The hard limit allows the search to be extended to three times the soft limit, but never uses more than half of the remaining time.
+    pub fn new(clock: Clock) -> Self {
+        let reserve = Self::OVERHEAD.min(clock.remaining/10);
+        let available = clock.remaining.saturating_sub(reserve);
+        let moves = clock.moves.max(1);
+        let soft = (available/moves + clock.increment*3/4).min(available);
+        let hard = (soft*3).min(available/2).max(soft);
+        TimeManager {
+            soft,
+            hard,
+            leader: None,
+            changed: Duration::ZERO,
+        }
+    }
+
+    ///Decides whether to stop searching after the elapsed time. The leader is the index of the most visited action and the lead is the difference in visits to the second most visited action. The rate is the number of search iterations per second.
+    pub fn stop(&mut self, elapsed: Duration, leader: usize, lead: u32, rate: f32) -> bool {
+        if self.leader != Some(leader) {
+            self.leader = Some(leader);
+            self.changed = elapsed;
+        }
+        
+        if elapsed >= self.hard {
+            return true;
+        }
+        
+        if elapsed < self.soft {
+            //The leader dominates when the remaining iterations can not overtake it
+            let left = (self.soft - elapsed).as_secs_f32()*rate;
+            (lead as f32) > left
+        } else {
+            //The leader is stable when it has not changed for the last half of the soft limit
+            (elapsed - self.changed) >= self.soft/2
+        }
+    }
+}
+
+impl<P: Player, A: Action, S: GameState<P,A>> MCTS<P, A, S> {
+    ///Call this method to search the root game state within the time given by a game clock (see TimeManager). Returns the best action, or None if there are no actions.
+    pub fn ponder_clock(&mut self, clock: Clock) -> Option<A> {
+        if self.root.gameover().is_some() {
+            return None;
+        }
+        let mut actions = 0;
+        self.root.actions(&mut |_| actions += 1);
+        if actions == 0 {
+            return None;
+        }
+
+        let mut manager = TimeManager::new(clock);
+        let start = Instant::now();
+        let n = self.info.n;
+        
+        loop {
+            self.ponder(100);
+            
+            let mut count = 0;
+            let mut first = (0,0);
+            let mut second = 0;
+            self.children(&mut |u,_,n| {
+                count += 1;
+                if n > first.1 {
+                    second = first.1;
+                    first = (u,n);
+                } else if n > second {
+                    second = n;
+                }
+            });
+            
+            if count < 2 {
+                //There is nothing to decide
+                break;
+            }
+            
+            let elapsed = start.elapsed();
+            let rate = ((self.info.n - n) as f32)/elapsed.as_secs_f32().max(1e-6);
+            if manager.stop(elapsed,first.0,first.1 - second,rate) {
+                break;
+            }
+        }
+        
+        self.best()
+    }
+}
@@ -0,0 +1,249 @@
+use super::*;
+use rand::SeedableRng;
+use rand::Rng as _;
+use rand::RngCore;
+use std::panic::{catch_unwind,AssertUnwindSafe};
+
+//Longest game that is walked before it is reported as endless
+const MAX_ACTIONS: usize = 10000;
+
+//Fewest decisive games that are won by the side to play before the results are reported as being from the wrong perspective
+const MIN_DECISIVE: usize = 10;
+
+//Game states that were already checked with their hashes, grouped by their display text so equal game states are found without comparing every pair
+type Seen<S> = HashMap<String,Vec<(S,u64)>>;
+
+///This struct describes a game state that failed a conformance check (see conformance). The actions lead from the starting game state to the game state that failed, and they are shrunk to as few actions as possible.
+#[derive(Debug,Clone)]
+pub struct Violation<A: Action> {
+    pub actions: Vec<A>,
+    pub message: String,
+}
+
+impl<A: Action> Display for Violation<A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,"{} after actions {:?}",self.message,self.actions)
+    }
+}
+
+//Message of a panic payload
+fn panic_message(payload: Box<dyn std::any::Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        format!("panicked: {}",s)
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        format!("panicked: {}",s)
+    } else {
+        String::from("panicked")
+    }
+}
+
+//Actions are compared by their debug text because they are not required to implement PartialEq
+fn list<P: Player, A: Action, S: GameState<P,A>>(state: &S) -> Vec<A> {
+    let mut actions = Vec::new();
+    state.actions(&mut |a| actions.push(a));
+    actions
+}
+
+fn text<T: Debug>(value: &T) -> String {
+    format!("{:?}",value)
+}
+
+//Checks the invariants of one game state. The game states that were already checked are used to find equal game states with different hashes.
+fn check<P,A,S>(state: &S, seen: &mut Seen<S>, seed: u64) -> Result<(),String>
+where P: Player, A: Action, S: GameState<P,A> + PartialEq {
+    let result = state.gameover();
+    if result != state.gameover() {
+        return Err(String::from("gameover is not deterministic"));
+    }
+
+    let hash = state.hash();
+    let group = seen.entry(state.to_string()).or_default();
+    match group.iter().find(|(s,_)| s == state) {
+        Some(&(_,h)) if h != hash => return Err(format!("hash {} differs from hash {} of an equal game state",hash,h)),
+        Some(_) => {},
+        None => group.push((state.clone(),hash)),
+    }
+
+    //The result and the score are both from the perspective of the side to play
+    if let Some(result) = result {
+        let score = state.score();
+        let consistent = match result {
+            GameResult::Win => score > 0.0,
+            GameResult::Lose => score < 0.0,
+            GameResult::Draw => score == 0.0,
+        };
+        if !(-1.0..=1.0).contains(&score) {
+            return Err(format!("score {} is not between -1 and 1",score));
+        }
+        if !consistent {
+            return Err(format!("score {} does not agree with the result {:?} for the side to play",score,result));
+        }
+        return Ok(());
+    }
+
+    //Actions are only listed for game states that are not over
+    let actions = list(state);
+    if text(&actions) != text(&list(state)) {
+        return Err(String::from("actions are not deterministic"));
+    }
+    if actions.is_empty() {
+        return Err(String::from("no actions before the game is over"));
+    }
+
+    let value = state.custom_evaluation();
+    if !(0.0..=1.0).contains(&value) {
+        return Err(format!("custom evaluation {} is not between 0 and 1",value));
+    }
+
+    let legal: Vec<String> = actions.iter().map(text).collect();
+    let mut rand = Rng::seed_from_u64(seed);
+    if let Some(a) = state.rollout_action(&mut || rand.next_u64()) {
+        if !legal.contains(&text(&a)) {
+            return Err(format!("rollout action {:?} is not legal",a));
+        }
+    }
+
+    let (_,t) = state.canonical();
+    for &a in actions.iter() {
+        let next = state.make(a);
+        let again = state.make(a);
+        if (text(&next) != text(&again)) || (next.hash() != again.hash()) {
+            return Err(format!("make is not deterministic for action {:?}",a));
+        }
+
+        let mut applied = state.clone();
+        applied.apply(a);
+        if (text(&next) != text(&applied)) || (next.hash() != applied.hash()) {
+            return Err(format!("apply does not agree with make for action {:?}",a));
+        }
+
+        if text(&state.inverse(state.transform(a,t),t)) != text(&a) {
+            return Err(format!("inverse does not undo transform {} for action {:?}",t,a));
+        }
+    }
+    Ok(())
+}
+
+//Plays the actions from the starting game state and checks each game state on the way. Returns the number of actions that lead to the first game state that fails with the reason, or None if every game state passes or the actions are not legal.
+fn replay<P,A,S>(start: &S, actions: &[A], seen: &mut Seen<S>, seed: u64) -> Option<(usize,String)>
+where P: Player, A: Action, S: GameState<P,A> + PartialEq {
+    let mut state = start.clone();
+    for i in 0..=actions.len() {
+        let checked = catch_unwind(AssertUnwindSafe(|| check(&state,seen,seed)));
+        match checked {
+            Ok(Ok(())) => {},
+            Ok(Err(message)) => return Some((i,message)),
+            Err(payload) => return Some((i,panic_message(payload))),
+        }
+
+        if i < actions.len() {
+            if state.gameover().is_some() {
+                return None;
+            }
+            let legal = list(&state).iter().any(|a| text(a) == text(&actions[i]));
+            if !legal {
+                return None;
+            }
+            state.apply(actions[i]);
+        }
+    }
+    None
+}
+
+//Removes actions from a failing sequence while it still fails, so the sequence that is reported is as short as possible
+fn shrink<P,A,S>(start: &S, actions: Vec<A>, message: String, seed: u64) -> Violation<A>
+where P: Player, A: Action, S: GameState<P,A> + PartialEq {
+    let mut actions = actions;
+    let mut message = message;
+    let mut i = 0;
+    while i < actions.len() {
+        let mut candidate = actions.clone();
+        candidate.remove(i);
+
+        let mut seen = Seen::default();
+        match replay(start,&candidate,&mut seen,seed) {
+            Some((n,m)) => {
+                candidate.truncate(n);
+                actions = candidate;
+                message = m;
+                i = 0;
+            },
+            None => i += 1,
+        }
+    }
+    Violation {actions, message}
+}
+
+///Check that a game state implementation is consistent by playing random games from the starting game state. Every game state that is reached is checked, and a violation with the shortest sequence of actions that shows the problem is returned for the first game state that fails. Panics are caught and reported as violations (e.g. a debug_assert in the "make" method). The panic hook is left alone, so the message of a caught panic is still printed by it.
+///
+///The checks are:
+///- "gameover", "actions", "make", and "apply" give the same results when they are called again, and "apply" agrees with "make".
+///- There are actions for every game state that is not over, and "rollout_action" chooses one of them.
+///- Game states that are equal have the same hash. Game states are compared with PartialEq, so it must ignore cached values such as the hash itself.
+///- The score of a game over state is between -1 and 1 and agrees with the result.
+///- The result is from the perspective of the side to play. When at least 10 decisive games end with an action that passes the turn, and the side to play wins every one of them, the results are reported as being from the perspective of the side that made the final action (this includes games where the final action always loses).
+///- The custom evaluation is between 0 and 1.
+///- "inverse" undoes "transform" for the symmetry of the canonical orientation.
+///- Every game ends within 10000 actions.
+pub fn conformance<P,A,S>(start: &S, games: usize, seed: u64) -> Result<(),Violation<A>>
+where P: Player, A: Action, S: GameState<P,A> + PartialEq {
+    let mut rand = Rng::seed_from_u64(seed);
+    let mut seen = Seen::default();
+    let mut failure = None;
+    //Decisive games that ended with an action that passed the turn, the number of them won by the side to play, and the actions of the last one
+    let (mut decisive,mut upsets,mut last) = (0,0,Vec::new());
+    'games: for _ in 0..games {
+        let mut state = start.clone();
+        let mut actions = Vec::new();
+        let mut mover = state.player();
+        loop {
+            let checked = catch_unwind(AssertUnwindSafe(|| check(&state,&mut seen,seed)));
+            let message = match checked {
+                Ok(Ok(())) => None,
+                Ok(Err(message)) => Some(message),
+                Err(payload) => Some(panic_message(payload)),
+            };
+            if let Some(message) = message {
+                failure = Some((actions,message));
+                break 'games;
+            }
+
+            if let Some(result) = state.gameover() {
+                if !actions.is_empty() && (mover != state.player()) && (result != GameResult::Draw) {
+                    decisive += 1;
+                    if result == GameResult::Win {
+                        upsets += 1;
+                    }
+                    last = actions;
+                }
+                break;
+            }
+            if actions.len() >= MAX_ACTIONS {
+                failure = Some((actions,format!("the game does not end within {} actions",MAX_ACTIONS)));
+                break 'games;
+            }
+
+            let legal = list(&state);
+            let action = legal[rand.gen_range(0..legal.len())];
+            mover = state.player();
+            if let Err(payload) = catch_unwind(AssertUnwindSafe(|| state.apply(action))) {
+                actions.push(action);
+                failure = Some((actions,panic_message(payload)));
+                break 'games;
+            }
+            actions.push(action);
+        }
+    }
+
+    if failure.is_none() && (decisive >= MIN_DECISIVE) && (upsets == decisive) {
+        let message = format!("the side to play wins all {} decisive games, so the results are from the perspective of the side that made the final action",decisive);
+        failure = Some((last,message));
+    }
+
+    match failure {
+        //An endless game or the results of many games cannot be shrunk by replaying one game
+        Some((actions,message)) if message.starts_with("the game does not end") || message.starts_with("the side to play wins") => Err(Violation {actions, message}),
+        Some((actions,message)) => Err(shrink(start,actions,message,seed)),
+        None => Ok(()),
+    }
+}
@@ -2,25 +2,44 @@
 
 mod search;
 mod builder;
+mod clock;
+mod agent;
+mod advance;
+mod protocol;
+mod cli;
+mod record;
+mod perft;
+mod conformance;
+pub use clock::{Clock,TimeManager};
+pub use agent::{Agent,Budget,MctsAgent,RandomAgent,AlphaBetaAgent,HumanAgent,Ordinal,Written,Outcome,play};
+pub use protocol::engine;
+pub use cli::cli;
+pub use record::{Record,Seat,read_records};
+pub use perft::{perft,divide};
+pub use conformance::{conformance,Violation};
 use std::fmt::Debug;
 use std::fmt::Display;
 use serde::Serialize;
+use instant::Instant;
 
 type HashMap<K,V> = rustc_hash::FxHashMap<K,V>;
 type Rng = rand_xorshift::XorShiftRng;
 
 ///This trait describes an allowed move for a game state. This type is passed to the "make" function to produce the next game state. The algorithm keeps track of all allowed actions for each game state that is visited. Limit the size of this type and prefer a contiguous memory layout for best performance (e.g. enum, integer). 
-pub trait Action: Copy + Clone + Debug {}
+pub trait Action: Copy + Clone + Debug {
+    ///Optional: Provide a key that identifies this action independent of the game state in which it is played (e.g. the index of the square a piece is placed on). Actions with the same key share statistics in the MAST rollout policy (see RolloutPolicy::Mast). Actions without a key are treated as if they have never been played.
+    fn key(&self) -> Option<u64> {None}
+}
 
 ///This trait describes the players in the game. For now it should be a two-state like a boolean.
 pub trait Player: Copy + Clone + Debug + PartialEq {}
 
 ///This enum describes the result of a game. The result should depict the outcome relative to the current player.
-#[derive(Debug)]
+#[derive(Debug,Copy,Clone,PartialEq)]
 pub enum GameResult {Win,Lose,Draw}
 
 ///This trait describes the current state of the game from which to begin searching for the best move.
-pub trait GameState<P: Player, A: Action>: Copy + Debug + Display {
+pub trait GameState<P: Player, A: Action>: Clone + Debug + Display {
 
     ///Iterate a list of legal actions for the current game state. Implementation should call "f" for each action.
     fn actions<F>(&self,f: &mut F) where F: FnMut(A);
@@ -28,6 +47,11 @@ pub trait GameState<P: Player, A: Action>: Copy + Debug + Display {
     ///Provide the next game state for the given action.
     fn make(&self,action: A) -> Self;
 
+    ///Optional: Apply the given action to the current game state in place. The search clones the root game state once per iteration and drives it to a leaf with this method, so game states that are expensive to copy (e.g. heap allocated boards or card decks) only need to be cloned once per iteration. The default implementation replaces the current game state with the result of "make".
+    fn apply(&mut self,action: A) {
+        *self = self.make(action);
+    }
+
     ///Indicate whether the current game state is in a game over condition. Return None when the game is still in play. Otherwise, return the result of the game from the current players perspective.
     fn gameover(&self) -> Option<GameResult>;
 
@@ -36,30 +60,175 @@ pub trait GameState<P: Player, A: Action>: Copy + Debug + Display {
     
     ///Optional: Provide a hash for the current game state. The hash is used to detect transpositions between game state when the "transposition" feature is activated. It must be sufficiently unique to avoid hash collisions with other game states. It is possible to have completely unique hashes for simple games like tic tac toe. An incremental hash may be a good approach in games with more complicated states like chess or checkers (see zobrist hashing).
     /// 
-    /// Transposition is experimental. Care should be taken for games that prohibit move cycles like chess.
+    ///The side to play is combined with the hash by the search, so it does not need to be included. Hash collisions can be detected by enabling "with_verified_transposition" for game states that implement PartialEq. Cycles back to a game state that is still being searched are scored as a draw.
     fn hash(&self) -> u64 {0}
 
+    ///Optional: Provide a hash for the canonical orientation of the current game state along with the index of the symmetry that maps the current game state onto it. Game states that are rotations or reflections of each other should have the same canonical hash. The symmetry index is passed to "transform" and "inverse" to map actions between orientations. Symmetry index 0 should be the identity.
+    /// 
+    ///Use the "with_symmetry" method in the MCTS builder to enable this feature.
+    fn canonical(&self) -> (u64,u8) {(self.hash(),0)}
+
+    ///Optional: Map an action in the orientation of the current game state to the canonical orientation using the given symmetry index.
+    fn transform(&self, action: A, _symmetry: u8) -> A {action}
+
+    ///Optional: Map an action in the canonical orientation back to the orientation of the current game state using the given symmetry index. This must undo "transform".
+    fn inverse(&self, action: A, _symmetry: u8) -> A {action}
+
     ///Optional: Override this method to provide a custom method for evaluating leaf nodes. The default algorithm for evaluating leaf nodes performs a random playout of the current game state using the GameState trait methods. This is a good starting point, but it should be possible to make a more efficient random playout function using the internals of the type that implements GameState. 
     /// 
     ///Overriding this method allows for other ways of evaluating leaf nodes. The evaluation must provide an estimate of the win probability for the player of the current game state. The value returned should be a random variable between 0 and 1 that is correlated with the probablity the current player will win the game.
     /// 
     /// Use the "with_custom_evaluation" method in the MCTS builder to enable this feature. 
     fn custom_evaluation(&self) -> f32 {0.5}
+
+    ///Optional: Provide the final score margin of a game over state from the perspective of the current player (e.g. the difference in disc counts). The margin must be normalized to a value between -1 and 1. This is only called when "gameover" returns a result. The default implementation uses 1 for a win, -1 for a loss, and 0 for a draw.
+    /// 
+    ///Use the "with_score_weight" method in the MCTS builder to search for larger margins.
+    fn score(&self) -> f32 {
+        match self.gameover() {
+            Some(GameResult::Win) => 1.0,
+            Some(GameResult::Lose) => -1.0,
+            _ => 0.0,
+        }
+    }
+
+    ///Optional: Provide a weight for choosing the given action during a random playout. Higher weights are preferred. Weights are only used by the epsilon greedy and softmax rollout policies (see RolloutPolicy). Actions with equal weights are chosen with equal probability.
+    fn rollout_weight(&self, _action: A) -> f32 {0.0}
+
+    ///Optional: Choose an action for a random playout without building the list of actions first. The rand callback provides uniformly distributed random numbers. Return None to fall back to choosing a uniformly random action.
+    /// 
+    ///Use the "with_rollout_policy" method in the MCTS builder with RolloutPolicy::Custom to enable this feature.
+    fn rollout_action<R>(&self, _rand: &mut R) -> Option<A> where R: FnMut() -> u64 {None}
+}
+
+///This trait describes how the actions of a game state are written as text in the standard notation of the game (e.g. "d3" in Reversi). It is optional, but it is required by "cli" and "engine" so that people, tools, and logs all use the same notation.
+pub trait Notation<P: Player, A: Action>: GameState<P,A> {
+    ///Parse the text of an action for the current game state. Return None if the text does not describe a legal action.
+    fn parse(&self, text: &str) -> Option<A>;
+
+    ///Write an action of the current game state as text. Parsing the text must give back the same action.
+    fn format(&self, action: A) -> String;
+
+    ///Optional: List the legal actions of the current game state as text in the order of the "actions" method. The list is empty when the game is over.
+    fn legal(&self) -> Vec<String> {
+        let mut legal = Vec::new();
+        if self.gameover().is_none() {
+            self.actions(&mut |a| legal.push(self.format(a)));
+        }
+        legal
+    }
 }
 
-#[derive(Debug)]
+///This trait describes how a game state is written as a compact position string that can be read back (like FEN in chess), so positions can be stored in test suites and bug reports without replaying the actions that reach them. It is optional.
+pub trait Position<P: Player, A: Action>: GameState<P,A> {
+    ///Write the game state as a position string. Reading the string with "from_position" must give back the same game state.
+    fn position(&self) -> String;
+
+    ///Read a game state from a position string. Return a message describing the problem if the string is not a valid position.
+    fn from_position(text: &str) -> Result<Self,String>;
+}
+
+///This enum selects how actions are chosen during random playouts.
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum RolloutPolicy {
+    ///Choose a uniformly random action. This is the default policy.
+    Uniform,
+    ///Choose the action with the highest rollout weight. With probability epsilon, choose a uniformly random action instead.
+    EpsilonGreedy(f32),
+    ///Choose an action with probability proportional to exp(weight/temperature).
+    Softmax(f32),
+    ///Choose an action with the rollout_action method of the game state.
+    Custom,
+    ///Move-Average Sampling Technique. The average outcome of each action key is learned during search. Choose an action with probability proportional to exp(average/temperature). Averages are between 0 and 1, so small temperatures (e.g. 0.1) are appropriate. Requires the "key" method of Action.
+    Mast(f32),
+}
+
+///This enum selects the value given to actions that have not been tried yet during selection (first play urgency). A finite value allows promising actions to be revisited before all of their siblings have been tried, which helps in games with many actions per turn. The value is also reported by the ply method for untried actions.
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum FirstPlayUrgency {
+    ///Untried actions are always chosen before any action is revisited. Untried actions are reported with a value of 0.5. This is the default.
+    Infinite,
+    ///Untried actions are valued at a fixed win probability between 0 and 1.
+    Value(f32),
+    ///Untried actions are valued at the win probability of their parent minus a reduction.
+    Reduction(f32),
+}
+
+///This enum selects the lookahead used to find decisive actions at each step of a random playout. Lookahead only uses the "make" and "gameover" methods of the game state. The number of extra calls to "make" is counted in Info::lookahead.
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum DecisiveMoves {
+    ///No lookahead. This is the default.
+    Off,
+    ///Play an action that wins the game immediately if there is one. Costs one call to "make" for each legal action.
+    Decisive,
+    ///Also avoid actions that allow the opponent to win the game immediately. Costs one call to "make" for each legal action and each legal reply.
+    AntiDecisive,
+}
+
+///This struct describes a playing strength as a temperature for sampling actions (see MCTS::sample) and a budget of search iterations. Use "level" for preset difficulty levels.
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub struct Strength {
+    ///Actions are sampled in proportion to visits^(1/temperature). Zero always plays the best action.
+    pub temperature: f32,
+    ///The number of search iterations before an action is chosen.
+    pub iterations: usize,
+}
+
+impl Strength {
+    ///The highest difficulty level.
+    pub const MAX_LEVEL: u8 = 10;
+
+    ///Maps a difficulty level from 1 (weakest) to MAX_LEVEL (strongest) to a strength. The budget doubles and the temperature drops with each level until the strongest level always plays the best action found. Levels outside this range are clamped.
+    pub fn level(level: u8) -> Self {
+        let level = level.clamp(1,Self::MAX_LEVEL);
+        let weakness = (Self::MAX_LEVEL - level) as f32;
+        Strength {
+            temperature: 2.0*weakness/((Self::MAX_LEVEL - 1) as f32),
+            iterations: 250 << (level - 1),
+        }
+    }
+}
+
+///This enum selects how often an observer is called while pondering (see with_observer).
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum Interval {
+    ///Call the observer after the given number of search iterations.
+    Iterations(u64),
+    ///Call the observer after the given number of milliseconds spent searching. Time between calls to ponder is not counted.
+    Millis(u64),
+}
+
+///This struct is a snapshot of the progress of the search. It is passed to an observer while pondering (see with_observer) or can be taken at any time with the "snapshot" method.
+#[derive(Debug,Clone)]
+pub struct Snapshot<A: Action> {
+    ///Number of search iterations since the search began.
+    pub iterations: u64,
+    ///Time spent searching since the search began.
+    pub elapsed: std::time::Duration,
+    ///The best action found so far (see MCTS::best).
+    pub best: Option<A>,
+    ///The actions in the first ply as reported by MCTS::ply.
+    pub ply: Vec<(A,f32,f32,f32)>,
+    ///The metrics of the search tree.
+    pub info: Info,
+}
+
+type Observer<A> = Box<dyn FnMut(&Snapshot<A>) + Send>;
+
+#[derive(Debug,Copy,Clone)]
 enum Node<P: Player, A: Action> {
     //sibling?, action, player, value, visits, child
     //s,a,p,w,n,c
     Unknown(bool,A),
-    Terminal(bool,A,P,f32),
+    Terminal(bool,A,P,f32,u32),
     Leaf(bool,A,P,f32,u32),
     Branch(bool,A,P,f32,u32,usize),
-    Transpose(bool,A,usize),
+    //sibling?, action, node, value, visits
+    Transpose(bool,A,usize,f32,u32),
 }
 
-///This struct provides metrics for the types of nodes in the search tree.
-#[derive(Default,Debug,Serialize,Copy,Clone)]
+///This struct provides metrics for the types of nodes in the search tree and the performance of the search.
+#[derive(Default,Debug,Serialize,Clone)]
 pub struct Info {
     pub q: f32,
     pub n: u32,
@@ -68,7 +237,34 @@ pub struct Info {
     pub terminal: u32,
     pub unknown: u32,
     pub transpose: u32,
+    ///Number of times the transposition map had a game state with the same hash that was not equal to the game state being searched. Collisions are only counted with "with_verified_transposition", and the node is not shared when one is found.
+    pub collisions: u32,
     pub bytes: usize,
+    ///Number of actions played in random playouts.
+    pub steps: u64,
+    ///Number of extra calls to "make" spent looking for decisive actions in random playouts.
+    pub lookahead: u64,
+    ///Deepest node reached by an iteration of the search.
+    pub depth_max: u32,
+    ///Average depth of the node reached by an iteration of the search.
+    pub depth_avg: f32,
+    ///Number of random playouts of each length. Longer playouts are counted with the length MAX_ROLLOUT.
+    pub rollouts: Vec<u64>,
+    ///Search iterations per second.
+    pub rate: f32,
+    ///Effective branching factor. This is the number of children per node of a uniform tree with the same number of branch nodes and the average depth.
+    pub branching: f32,
+    ///Number of times the transposition map was searched.
+    pub lookups: u32,
+    ///Fraction of searches of the transposition map that found a transposition.
+    pub hit_rate: f32,
+    ///Number of nodes at each depth of the tree. The root is at depth zero.
+    pub nodes: Vec<u32>,
+}
+
+impl Info {
+    ///The longest rollout length counted separately in "rollouts".
+    pub const MAX_ROLLOUT: usize = 255;
 }
 
 //PMLFIXME add an API that does "pretraining". It should take a Vec<f32> and train on the random playout policy. This should be used "offline" by the developer.
@@ -79,13 +275,35 @@ pub struct MCTS<P: Player, A: Action, S: GameState<P,A>> {
     expansion: u32,
     use_custom_evaluation: bool,
     use_transposition: bool,
+    batch: usize,
+    policy: RolloutPolicy,
+    decisive: DecisiveMoves,
+    fpu: FirstPlayUrgency,
+    depth: u32,
+    lambda: f32,
+    score_weight: f32,
+    draw: f32,
 
     ///Provides metrics about the shape and size of the game tree. For informational purposes only.
     pub info: Info,
     
     root: S,
     stack: Vec<Node<P,A>>,
+    margins: Vec<f32>,
     actions: Vec<A>,
+    weights: Vec<f32>,
+    trace: Vec<(P,A)>,
+    mast: HashMap<(bool,u64),(f32,u32)>,
     rand: Rng,
-    map: HashMap<u64,usize>,
+    use_symmetry: bool,
+    map: HashMap<(u64,bool),(usize,u8)>,
+    verify: Option<fn(&S,&S) -> bool>,
+    states: HashMap<usize,S>,
+    path: Vec<usize>,
+    reach: usize,
+    iterations: u64,
+    depth_sum: u64,
+    elapsed: std::time::Duration,
+    observer: Option<(Interval,Observer<A>)>,
+    observed: (std::time::Duration,u64),
 }
\ No newline at end of file
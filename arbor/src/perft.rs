@@ -0,0 +1,83 @@
+use super::*;
+use std::sync::atomic::{AtomicUsize,Ordering};
+
+//Counts of subtrees keyed by the hash, the side to play, and the remaining depth
+type Cache = HashMap<(u64,bool,u32),u64>;
+
+//Counts the leaves below the game state, where the root player is used to tell the sides apart in the cache
+fn count<P,A,S>(state: &S, depth: u32, root: P, cache: &mut Option<Cache>) -> u64
+where P: Player, A: Action, S: GameState<P,A> {
+    if (depth == 0) || state.gameover().is_some() {
+        return 1;
+    }
+
+    let key = (state.hash(),state.player() == root,depth);
+    if depth > 1 {
+        if let Some(&n) = cache.as_ref().and_then(|c| c.get(&key)) {
+            return n;
+        }
+    }
+
+    let mut total = 0;
+    state.actions(&mut |action| {
+        total += if depth == 1 {
+            1
+        } else {
+            count(&state.make(action),depth - 1,root,cache)
+        };
+    });
+
+    if depth > 1 {
+        if let Some(c) = cache.as_mut() {
+            c.insert(key,total);
+        }
+    }
+    total
+}
+
+///Count the leaves of the game tree below the game state to the given depth (perft). A game over state counts as a leaf even if it is above the given depth, so the count at a deep enough depth is the number of different games that can be played. Counts from a trusted source verify the "actions" and "make" methods of a game.
+///
+///Enable the cache to count the subtree of each game state only once when it is reached again by a transposition. The cache uses GameState::hash, so it must not be enabled for game states that do not provide a hash.
+pub fn perft<P,A,S>(state: &S, depth: u32, cache: bool) -> u64
+where P: Player, A: Action, S: GameState<P,A> {
+    let mut cache = cache.then(Cache::default);
+    count(state,depth,state.player(),&mut cache)
+}
+
+///Count the leaves below each action of the game state like "perft" (also known as divide), so a wrong count can be followed down to the game state where the actions are wrong. The actions are counted in parallel across the available threads. The sum of the counts is the count of "perft" unless the game is over.
+pub fn divide<P,A,S>(state: &S, depth: u32, cache: bool) -> Vec<(A,u64)>
+where P: Player + Sync, A: Action + Send + Sync, S: GameState<P,A> + Sync {
+    let mut actions = Vec::new();
+    if (depth > 0) && state.gameover().is_none() {
+        state.actions(&mut |action| actions.push(action));
+    }
+
+    let threads = std::thread::available_parallelism().map_or(1,|n| n.get()).min(actions.len());
+    let next = AtomicUsize::new(0);
+    let mut counts = vec![0;actions.len()];
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..threads).map(|_| {
+            let (actions,next) = (&actions,&next);
+            scope.spawn(move || {
+                let mut cache = cache.then(Cache::default);
+                let mut counts = Vec::new();
+                loop {
+                    let i = next.fetch_add(1,Ordering::Relaxed);
+                    if i >= actions.len() {
+                        break;
+                    }
+                    counts.push((i,count(&state.make(actions[i]),depth - 1,state.player(),&mut cache)));
+                }
+                counts
+            })
+        }).collect();
+
+        for handle in handles {
+            for (i,n) in handle.join().expect("perft thread panicked") {
+                counts[i] = n;
+            }
+        }
+    });
+
+    actions.into_iter().zip(counts).collect()
+}
@@ -0,0 +1,235 @@
+use super::*;
+use std::io::BufRead;
+use std::io::Write;
+
+struct Engine<P: Player, A: Action, S: GameState<P,A>, F: FnMut(S) -> MCTS<P,A,S>> {
+    start: S,
+    state: S,
+    moves: Vec<A>,
+    mcts: MCTS<P,A,S>,
+    configure: F,
+}
+
+impl<P: Player, A: Action + PartialEq, S: Notation<P,A>, F: FnMut(S) -> MCTS<P,A,S>> Engine<P,A,S,F> {
+    fn side(&self, player: P) -> &'static str {
+        if player == self.start.player() {"p1"} else {"p2"}
+    }
+
+    //Parses the actions in order from the game state
+    fn parse(state: &S, moves: &[&str]) -> Result<Vec<A>,String> {
+        let mut state = state.clone();
+        let mut actions = Vec::new();
+        for text in moves {
+            let action = state.parse(text).ok_or(format!("illegal action {}",text))?;
+            state.apply(action);
+            actions.push(action);
+        }
+        Ok(actions)
+    }
+
+    //Plays the actions from the current game state and keeps the search tree
+    fn play(&mut self, moves: &[A]) {
+        for &action in moves {
+            self.state.apply(action);
+            self.mcts.advance(action);
+            self.moves.push(action);
+        }
+    }
+
+    //Starts over from the starting game state
+    fn restart(&mut self, moves: &[A]) {
+        self.state = self.start.clone();
+        self.moves.clear();
+        self.mcts = (self.configure)(self.state.clone());
+        self.play(moves);
+    }
+
+    fn position(&mut self, moves: &[A]) {
+        if moves.starts_with(&self.moves) {
+            //The position follows the current game state, so the search tree is kept
+            let n = self.moves.len();
+            self.play(&moves[n..]);
+        } else {
+            self.restart(moves);
+        }
+    }
+
+    fn undo(&mut self) -> Result<(),String> {
+        let mut moves = self.moves.clone();
+        moves.pop().ok_or("no action to undo")?;
+        self.restart(&moves);
+        Ok(())
+    }
+
+    fn info<W: Write>(&self, output: &mut W) -> std::io::Result<()> {
+        let info = &self.mcts.info;
+        let best = self.mcts.best().map(|a| self.state.format(a));
+        writeln!(
+            output,
+            "info visits {} depth {:.1} rate {:.0} value {:.3} best {}",
+            info.n,
+            info.depth_avg,
+            info.rate,
+            info.q,
+            best.unwrap_or(String::from("none")),
+        )
+    }
+
+    fn go<W: Write>(&mut self, budget: Budget, output: &mut W) -> std::io::Result<Result<(),String>> {
+        if self.state.gameover().is_some() {
+            return Ok(Err(String::from("game over")));
+        }
+
+        let start = Instant::now();
+        let mut last = start;
+        let interval = std::time::Duration::from_millis(100);
+        loop {
+            let n = match budget {
+                Budget::Iterations(n) => n.saturating_sub(self.mcts.info.n as usize).min(100),
+                Budget::Time(t) => if start.elapsed() < t {100} else {0},
+                Budget::Strength(s) => s.iterations.saturating_sub(self.mcts.info.n as usize).min(100),
+            };
+            if n == 0 {
+                break;
+            }
+
+            self.mcts.ponder(n);
+            if last.elapsed() >= interval {
+                last = Instant::now();
+                self.info(output)?;
+                output.flush()?;
+            }
+        }
+
+        let action = match budget {
+            Budget::Strength(s) => self.mcts.sample(s.temperature),
+            _ => self.mcts.best(),
+        };
+        self.info(output)?;
+        match action {
+            Some(a) => writeln!(output,"bestmove {}",self.state.format(a))?,
+            None => return Ok(Err(String::from("no action found"))),
+        }
+        Ok(Ok(()))
+    }
+
+    //Runs one command and returns false to quit
+    fn command<W: Write>(&mut self, line: &str, output: &mut W) -> std::io::Result<bool> {
+        let mut words = line.split_whitespace();
+        let command = match words.next() {
+            Some(command) => command,
+            None => return Ok(true),
+        };
+        let args: Vec<&str> = words.collect();
+        let number = |i: usize| -> Result<u64,String> {
+            let s = args.get(i).ok_or("missing argument")?;
+            s.parse::<u64>().map_err(|_| format!("not an integer {}",s))
+        };
+
+        let result = match command {
+            "quit" => return Ok(false),
+            "position" => Self::parse(&self.start,&args).map(|moves| self.position(&moves)),
+            "play" => Self::parse(&self.state,&args).map(|moves| self.play(&moves)),
+            "undo" => self.undo(),
+            "go" => {
+                let budget = match args.first() {
+                    Some(&"iterations") => number(1).map(|n| Budget::Iterations(n as usize)),
+                    Some(&"time") => number(1).map(|ms| Budget::Time(std::time::Duration::from_millis(ms))),
+                    Some(&"level") => number(1).map(|l| Budget::Strength(Strength::level(l.min(255) as u8))),
+                    _ => Err(String::from("expected iterations, time, or level")),
+                };
+                match budget {
+                    Ok(budget) => self.go(budget,output)?,
+                    Err(e) => Err(e),
+                }
+            },
+            "analysis" => {
+                let mut lines = Vec::new();
+                self.mcts.ply(&mut |(a,w,s,m)| lines.push((a,w,s,m)));
+                for (a,w,s,m) in lines {
+                    writeln!(output,"action {} value {:.3} confidence {:.3} margin {:.3}",self.state.format(a),w,s,m)?;
+                }
+                Ok(())
+            },
+            "actions" => {
+                writeln!(output,"{}",self.state.legal().join(" "))?;
+                Ok(())
+            },
+            "moves" => {
+                let mut state = self.start.clone();
+                let mut moves = Vec::new();
+                for &a in self.moves.iter() {
+                    moves.push(state.format(a));
+                    state.apply(a);
+                }
+                writeln!(output,"{}",moves.join(" "))?;
+                Ok(())
+            },
+            "show" => {
+                writeln!(output,"{}",self.state)?;
+                Ok(())
+            },
+            "side" => {
+                let side = match self.state.gameover() {
+                    Some(_) => "none",
+                    None => self.side(self.state.player()),
+                };
+                writeln!(output,"{}",side)?;
+                Ok(())
+            },
+            "result" => {
+                let p = self.state.player();
+                let result = match self.state.gameover() {
+                    None => "none",
+                    Some(GameResult::Draw) => "draw",
+                    Some(GameResult::Win) => self.side(p),
+                    Some(GameResult::Lose) => if self.side(p) == "p1" {"p2"} else {"p1"},
+                };
+                writeln!(output,"{}",result)?;
+                Ok(())
+            },
+            _ => Err(format!("unknown command {}",command)),
+        };
+
+        if let Err(e) = result {
+            writeln!(output,"error {}",e)?;
+        }
+        writeln!(output)?;
+        output.flush()?;
+        Ok(true)
+    }
+}
+
+///Run a long lived engine that reads commands from the input and writes responses to the output until the input is closed or "quit" is read. The configure function builds the search for a game state (e.g. |state| MCTS::new(state).with_transposition()). The engine keeps one search tree for the game (see MCTS::advance), so the search from previous turns is not lost.
+///
+///Actions are written in the notation of the game (see Notation). The side to play in the starting game state is "p1" and the other side is "p2". Each response ends with an empty line, and a failed command responds with a line that starts with "error".
+///
+///Commands:
+///- "position a b ...": start over from the starting game state and play the given actions. The search tree is kept when the actions follow the current game state.
+///- "play a b ...": play the given actions from the current game state.
+///- "undo": take back the last action.
+///- "go iterations n", "go time ms", or "go level l": search until the root has n visits, for the given time, or like the given difficulty level (see Strength). Analysis lines starting with "info" are printed while searching, followed by "bestmove a".
+///- "analysis": print a line for each action with its notation, value, confidence, and score margin (see MCTS::ply).
+///- "actions": print the legal actions.
+///- "moves": print the actions played from the starting game state.
+///- "show", "side", "result": print the game state, the side to play, and the winning side or "draw". The side and result are "none" when there is none.
+///- "quit": stop the engine.
+pub fn engine<P,A,S,F,R,W>(start: S, configure: F, input: R, output: &mut W) -> std::io::Result<()>
+where P: Player, A: Action + PartialEq, S: Notation<P,A>, F: FnMut(S) -> MCTS<P,A,S>, R: BufRead, W: Write {
+    let mut configure = configure;
+    let mcts = configure(start.clone());
+    let mut engine = Engine {
+        start: start.clone(),
+        state: start,
+        moves: Vec::new(),
+        mcts,
+        configure,
+    };
+
+    for line in input.lines() {
+        if !engine.command(&line?,output)? {
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,146 @@
+use super::*;
+use serde::Deserialize;
+use std::io::BufRead;
+use std::io::Write;
+
+//Side of the player, where p1 is the side to play in the starting game state
+pub(crate) fn side<P: Player>(start: P, player: P) -> &'static str {
+    if player == start {"p1"} else {"p2"}
+}
+
+//Winning side of a game over state
+pub(crate) fn winner<P: Player, A: Action, S: GameState<P,A>>(start: P, state: &S) -> &'static str {
+    match state.gameover() {
+        None => "none",
+        Some(GameResult::Draw) => "draw",
+        Some(GameResult::Win) => side(start,state.player()),
+        Some(GameResult::Lose) => if side(start,state.player()) == "p1" {"p2"} else {"p1"},
+    }
+}
+
+///This struct describes one side of a recorded game.
+#[derive(Debug,Default,Clone,PartialEq,Serialize,Deserialize)]
+#[serde(default)]
+pub struct Seat {
+    ///The name of the player (e.g. a person or an engine in a tournament).
+    pub name: String,
+    ///The configuration of the agent that chose the actions for this side (e.g. {"type":"mcts","time":1000}). It is not interpreted when the record is read.
+    pub agent: serde_json::Value,
+}
+
+impl Seat {
+    pub fn new(name: &str, agent: serde_json::Value) -> Self {
+        Seat {name: String::from(name), agent}
+    }
+}
+
+///This struct describes a saved game. Records are written as one line of JSON each (JSONL), so a file of games can be appended to as games finish. The actions are written in the notation of the game (see Notation) starting from the starting game state, where "p1" is the side to play.
+#[derive(Debug,Default,Clone,PartialEq,Serialize,Deserialize)]
+#[serde(default)]
+pub struct Record {
+    ///The name of the game (e.g. "reversi").
+    pub game: String,
+    ///The date the game was played as YYYY-MM-DD in UTC.
+    pub date: String,
+    pub p1: Seat,
+    pub p2: Seat,
+    ///The number of actions at the start that were not chosen by the players (e.g. a random opening in a tournament).
+    pub opening: usize,
+    ///The actions played in order from the starting game state.
+    pub moves: Vec<String>,
+    ///The winning side "p1" or "p2", "draw", or "none" for an unfinished game.
+    pub result: String,
+    ///Indicates that the side to play at the end of the game resigned.
+    pub resigned: bool,
+}
+
+impl Record {
+    ///Create a record of a game that has not started yet, played today.
+    pub fn new(game: &str, p1: Seat, p2: Seat) -> Self {
+        Record {
+            game: String::from(game),
+            date: time::OffsetDateTime::now_utc().format("%F"),
+            p1,
+            p2,
+            opening: 0,
+            moves: Vec::new(),
+            result: String::from("none"),
+            resigned: false,
+        }
+    }
+
+    ///Set the actions played from the starting game state and the result they lead to. A resignation is a loss for the side to play after the last action.
+    pub fn with_actions<P,A,S>(mut self, start: &S, actions: &[A], resigned: bool) -> Self
+    where P: Player, A: Action, S: Notation<P,A> {
+        let p = start.player();
+        let mut state = start.clone();
+        self.moves.clear();
+        for &action in actions {
+            self.moves.push(state.format(action));
+            state.apply(action);
+        }
+
+        self.resigned = resigned && state.gameover().is_none();
+        self.result = if self.resigned {
+            String::from(if side(p,state.player()) == "p1" {"p2"} else {"p1"})
+        } else {
+            String::from(winner(p,&state))
+        };
+        self
+    }
+
+    ///Replay the actions from the starting game state and return the final game state. Each action must be legal, and the result must match the final game state.
+    pub fn replay<P,A,S>(&self, start: S) -> Result<S,String>
+    where P: Player, A: Action, S: Notation<P,A> {
+        if self.opening > self.moves.len() {
+            return Err(format!("opening of {} actions is longer than the game",self.opening));
+        }
+
+        let p = start.player();
+        let mut state = start;
+        for (i,text) in self.moves.iter().enumerate() {
+            if state.gameover().is_some() {
+                return Err(format!("action {} ({}) is played after the game is over",i + 1,text));
+            }
+            match state.parse(text) {
+                Some(action) => state = state.make(action),
+                None => return Err(format!("action {} ({}) is not legal",i + 1,text)),
+            }
+        }
+
+        let result = match (self.resigned,state.gameover()) {
+            (true,Some(_)) => return Err(String::from("resigned after the game is over")),
+            (true,None) => if side(p,state.player()) == "p1" {"p2"} else {"p1"},
+            (false,_) => winner(p,&state),
+        };
+        if self.result != result {
+            return Err(format!("result {} does not match the final game state {}",self.result,result));
+        }
+        Ok(state)
+    }
+
+    ///Write the record as one line of JSON.
+    pub fn write<W: Write>(&self, output: &mut W) -> std::io::Result<()> {
+        let line = serde_json::to_string(self).map_err(std::io::Error::other)?;
+        writeln!(output,"{}",line)
+    }
+
+    ///Read a record from one line of JSON.
+    pub fn parse(line: &str) -> Result<Self,String> {
+        serde_json::from_str(line).map_err(|e| e.to_string())
+    }
+}
+
+///Read the records from the lines of the input, skipping empty lines. The error for a line that is not a record includes its line number.
+pub fn read_records<R: BufRead>(input: R) -> Result<Vec<Record>,String> {
+    let mut records = Vec::new();
+    for (i,line) in input.lines().enumerate() {
+        let line = line.map_err(|e| e.to_string())?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = Record::parse(&line).map_err(|e| format!("line {}: {}",i + 1,e))?;
+        records.push(record);
+    }
+    Ok(records)
+}
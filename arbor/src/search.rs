@@ -1,16 +1,11 @@
 use super::*;
 use rand::SeedableRng;
 use rand::RngCore;
+use rand::Rng as _;
 
-impl GameResult {
-    #[inline]
-    fn value(&self) -> f32 {
-        match *self {
-            GameResult::Win => 1.0,
-            GameResult::Lose => 0.0,
-            GameResult::Draw => 0.5,
-        }
-    }
+enum Selection<P: Player, S> {
+    Pending(S),
+    Done(P,f32,f32),
 }
 
 impl<P: Player, A: Action, S: GameState<P,A>> MCTS<P, A, S> {
@@ -22,12 +17,34 @@ impl<P: Player, A: Action, S: GameState<P,A>> MCTS<P, A, S> {
             expansion: 0,
             use_custom_evaluation: false,
             use_transposition: false,
+            batch: 8,
+            policy: RolloutPolicy::Uniform,
+            decisive: DecisiveMoves::Off,
+            fpu: FirstPlayUrgency::Infinite,
+            depth: u32::MAX,
+            lambda: 1.0,
+            score_weight: 0.0,
+            draw: 0.5,
             info: Info::default(),
-            root: root,
+            root,
             stack: Vec::new(),
+            margins: Vec::new(),
             actions: Vec::new(),
+            weights: Vec::new(),
+            trace: Vec::new(),
+            mast: HashMap::default(),
             rand: Rng::from_seed(s),
+            use_symmetry: false,
             map: HashMap::default(),
+            verify: None,
+            states: HashMap::default(),
+            path: Vec::new(),
+            reach: 0,
+            iterations: 0,
+            depth_sum: 0,
+            elapsed: std::time::Duration::ZERO,
+            observer: None,
+            observed: (std::time::Duration::ZERO,0),
         }
     }
 
@@ -36,23 +53,91 @@ impl<P: Player, A: Action, S: GameState<P,A>> MCTS<P, A, S> {
         let mut best = None;
         let mut max = -0.1;
         
-        self.ply(&mut |(a,w,_s)| {
+        self.ply(&mut |(a,w,_s,_m)| {
             if max < w {
                 max = w;
                 best = Some(a);
             }
         });
         
-        return best;
+        best
+    }
+
+    ///Pick a random move in proportion to the number of visits of each action raised to the power of 1/temperature. A temperature of zero picks the best move and larger temperatures give weaker and more varied play (e.g. for opening diversity in self-play). Returns None if ponder has not yet been called.
+    pub fn sample(&mut self, temperature: f32) -> Option<A> {
+        assert!(temperature >= 0.0,"The temperature must not be negative.");
+        if temperature == 0.0 {
+            return self.best();
+        }
+        
+        //Game over states are not explored, so they have few visits even when they are the best action. The ply method reports them with a confidence of zero.
+        let mut max = -0.1;
+        let mut exact = false;
+        self.ply(&mut |(_a,w,s,_m)| {
+            if max < w {
+                max = w;
+                exact = s == 0.0;
+            }
+        });
+        if exact {
+            return self.best();
+        }
+        
+        let mut actions = Vec::new();
+        let mut weights = Vec::new();
+        self.children(&mut |_,a,n| {
+            actions.push(a);
+            weights.push((n as f32).ln());
+        });
+        
+        if weights.iter().all(|w| w.is_infinite()) {
+            //No action has been visited yet
+            return self.best();
+        }
+        
+        self.weights = weights;
+        let i = self.gibbs(temperature);
+        let t = self.symmetry(&self.root);
+        Some(self.root.inverse(actions[i],t))
+    }
+    
+    //Iterate through the index, action (in the orientation of the tree), and visits of each action in the first ply.
+    pub(crate) fn children<F>(&self, f: &mut F) where F: FnMut(usize,A,u32) {
+        if let Some(&Node::Branch(_,_,_,_,_,c)) = self.stack.first() {
+            let mut sibling = Some(c);
+            while let Some(u) = sibling {
+                let (s,a,n) = match self.stack[u] {
+                    Node::Unknown(s,a) => (s,a,0),
+                    Node::Terminal(s,a,_,_,n) |
+                    Node::Leaf(s,a,_,_,n) |
+                    Node::Branch(s,a,_,_,n,_) |
+                    Node::Transpose(s,a,_,_,n) => (s,a,n),
+                };
+                f(u,a,n);
+                sibling = s.then(||u+1);
+            }
+        }
+    }
+    
+    ///Search until the iteration budget of the given strength is spent, then sample a move with its temperature (see sample and Strength).
+    pub fn play_like(&mut self, strength: Strength) -> Option<A> {
+        let n = strength.iterations.saturating_sub(self.info.n as usize);
+        self.ponder(n);
+        self.sample(strength.temperature)
     }
 
-    ///Iterate through the actions in the first ply. The callback f is called for each action in the first ply with a tuple of (a, w, s) where a is the action, w is the expected value of the action, and s is the confidence in the value of the action. s is similar to standard deviation where closer to zero is more confident.
-    pub fn ply<F>(&self, f: &mut F) where F: FnMut((A,f32,f32)) {
-        if self.stack.len() == 0 {
+    ///Iterate through the actions in the first ply. The callback f is called for each action in the first ply with a tuple of (a, w, s, m) where a is the action, w is the expected value of the action, s is the confidence in the value of the action, and m is the expected score margin of the action between -1 and 1 (see GameState::score). s is similar to standard deviation where closer to zero is more confident.
+    pub fn ply<F>(&self, f: &mut F) where F: FnMut((A,f32,f32,f32)) {
+        if self.stack.is_empty() {
             return;
         }
 
-        if let Node::Branch(_,_,player,_,_,c) = self.stack[0] {
+        if let Node::Branch(_,_,player,w,n,c) = self.stack[0] {
+            let root = &self.root;
+            let t = self.symmetry(root);
+            let mut f = |(a,w,e,m)| f((root.inverse(a,t),w,e,m));
+            let fpu = self.first_play(w,n);
+            let fpu = if fpu.is_finite() {fpu} else {0.5};
             let mut sibling = Some(c);
             while let Some(u) = sibling {
                 match self.stack[u] {
@@ -60,22 +145,42 @@ impl<P: Player, A: Action, S: GameState<P,A>> MCTS<P, A, S> {
                     Node::Branch(s,a,p,w,n,_) => {
                         let n = n as f32;
                         let w = w/n;
-                        let w = if p == player {w} else {1.0 - w};
+                        let m = self.margins[u]/n;
+                        let (w,m) = if p == player {(w,m)} else {(1.0 - w,-m)};
                         let e = 0.5/n + (w*(1.0 - w)/n).sqrt();
-                        f((a,w,e));
+                        f((a,w,e,m));
                         sibling = s.then(||u+1);
                     },
-                    Node::Terminal(s,a,p,w) => {
-                        let w = if p == player {w} else {1.0 - w};
-                        f((a,w,0.0));
+                    Node::Terminal(s,a,p,w,_) => {
+                        let m = self.margins[u];
+                        let (w,m) = if p == player {(w,m)} else {(1.0 - w,-m)};
+                        f((a,w,0.0,m));
                         sibling = s.then(||u+1);
                     },
                     Node::Unknown(s,a) => {
-                        f((a,0.5,0.5));
+                        f((a,fpu,0.5,0.0));
+                        sibling = s.then(||u+1);
+                    },
+                    Node::Transpose(s,a,_,_,0) => {
+                        f((a,fpu,0.5,0.0));
                         sibling = s.then(||u+1);
                     },
-                    Node::Transpose(_,_,_) => 
-                        panic!("Transpositions should not be possible at root ply")
+                    Node::Transpose(s,a,v,w,n) => {
+                        //Report the statistics of the edge, which may be shared with other actions at the root. They are from the perspective of the player of the shared node.
+                        let p = match self.stack[v] {
+                            Node::Terminal(_,_,p,_,_) |
+                            Node::Leaf(_,_,p,_,_) |
+                            Node::Branch(_,_,p,_,_,_) => p,
+                            _ => player,
+                        };
+                        let n = n as f32;
+                        let w = w/n;
+                        let m = self.margins[u]/n;
+                        let (w,m) = if p == player {(w,m)} else {(1.0 - w,-m)};
+                        let e = 0.5/n + (w*(1.0 - w)/n).sqrt();
+                        f((a,w,e,m));
+                        sibling = s.then(||u+1);
+                    }
                 }
             }
         } else {
@@ -83,50 +188,328 @@ impl<P: Player, A: Action, S: GameState<P,A>> MCTS<P, A, S> {
         }
     }
     
+    ///The MAST average outcome of the action when it is played by the given player, between 0 and 1 (see RolloutPolicy::Mast). Returns None if the action has no key or has not been played in a random playout by that player. For informational purposes only.
+    pub fn mast(&self, player: P, action: A) -> Option<f32> {
+        let side = player == self.root.player();
+        action.key()
+            .and_then(|k| self.mast.get(&(side,k)))
+            .map(|&(w,n)| w/(n as f32))
+    }
+    
     ///Call this method to search the root game state a given number of iterations. This method may be called any number of times to improve the search results. Call ply or best to get the current search results.
     pub fn ponder(&mut self, n: usize) {
-        if self.stack.len() == 0 {
-            let mut actions = Vec::new();
-            self.root.actions(&mut |a| actions.push(a));
+        if self.stack.is_empty() {
+            self.expand_root();
+        }
+        
+        let mut start = Instant::now();
+        for _ in 0..n {
+            let mut state = self.root.clone();
+            self.reach = 0;
+            self.go(&mut state,0);
+            self.reached();
+            self.observe(&mut start);
+        }
+        
+        self.elapsed += start.elapsed();
+        self.statistics();
+    }
+    
+    ///Call this method to search the root game state a given number of iterations using a batch evaluator for leaf nodes. This is intended for expensive evaluators (e.g. a neural network) that are much faster when called on many game states at once. Random playouts and the custom evaluation method are not used in this mode.
+    /// 
+    ///Up to "batch size" leaf nodes are collected before the evaluator is called (see with_batch_size). A virtual loss is applied to each node along the path to a pending leaf so that the leaves in a batch are spread across the tree. The evaluator f is called with a slice of leaf game states and a slice of values of the same length to fill in. Each value must be an estimate of the win probability for the player of the corresponding game state, the same as custom_evaluation.
+    pub fn ponder_batch<F>(&mut self, n: usize, f: &mut F) where F: FnMut(&[S],&mut [f32]) {
+        if self.stack.is_empty() {
+            self.expand_root();
+        }
+        
+        let mut states = Vec::with_capacity(self.batch);
+        let mut paths = Vec::with_capacity(self.batch);
+        let mut values = Vec::with_capacity(self.batch);
+        let mut i = 0;
+        let mut start = Instant::now();
+        
+        while i < n {
+            states.clear();
+            paths.clear();
             
+            while (i < n) && (states.len() < self.batch) {
+                let mut path = Vec::new();
+                let mut state = self.root.clone();
+                self.reach = 0;
+                let selection = self.select(&mut state,0,None,&mut path);
+                self.reached();
+                match selection {
+                    Selection::Pending(state) => {
+                        states.push(state);
+                        paths.push(path);
+                    },
+                    Selection::Done(p,v,m) => {
+                        self.backup(&path,p,v,m);
+                    }
+                }
+                i += 1;
+            }
             
-            self.stack.push(Node::Leaf(
-                false,
-                // This action is never used, so it doesn't matter what it is
-                *actions.first().expect("should have at least one action"),
-                self.root.player(),
-                0.5,
-                1
-            ));
+            if !states.is_empty() {
+                values.clear();
+                values.resize(states.len(),0.5);
+                f(&states,&mut values);
+                
+                for ((state,path),&v) in states.iter().zip(paths.iter()).zip(values.iter()) {
+                    //The evaluator only estimates the win probability, so the margin is estimated from it
+                    self.backup(path,state.player(),v,2.0*v - 1.0);
+                }
+            }
             
-            self.info.leaf = 1;
+            //Pending leaves hold a virtual loss, so the observer is only called between batches
+            self.observe(&mut start);
+        }
+        
+        self.elapsed += start.elapsed();
+        self.statistics();
+    }
+    
+    ///Take a snapshot of the progress of the search. This is the same snapshot that is passed to an observer (see with_observer).
+    pub fn snapshot(&self) -> Snapshot<A> {
+        let mut ply = Vec::new();
+        self.ply(&mut |e| ply.push(e));
+        
+        Snapshot {
+            iterations: self.iterations,
+            elapsed: self.elapsed,
+            best: self.best(),
+            ply,
+            info: self.info.clone(),
+        }
+    }
+    
+    //Calls the observer once its interval has passed. The time since "start" is added to the elapsed time first, so the snapshot includes the current call to ponder. Intervals of time are measured in time spent searching, so the time between calls to ponder is not counted.
+    fn observe(&mut self, start: &mut Instant) {
+        let (time,iterations) = self.observed;
+        let due = match self.observer {
+            Some((Interval::Iterations(n),_)) => self.iterations - iterations >= n,
+            Some((Interval::Millis(t),_)) => (self.elapsed + start.elapsed() - time).as_millis() >= t as u128,
+            None => false,
+        };
+        
+        if due {
+            let now = Instant::now();
+            self.elapsed += now - *start;
+            *start = now;
+            self.observed = (self.elapsed,self.iterations);
+            self.statistics();
             
-            //Call go once with expansion set to zero to force the root to expand 
-            let root = self.root;
-            let expansion = self.expansion;
-            self.expansion = 0;
-            self.go(&root, 0);
-            self.expansion = expansion;
-            self.ponder(n - 1);
+            let snapshot = self.snapshot();
+            if let Some((_,f)) = self.observer.as_mut() {
+                f(&snapshot);
+            }
+        }
+    }
+    
+    //Records the depth of the last iteration.
+    fn reached(&mut self) {
+        let depth = self.reach as u32;
+        self.iterations += 1;
+        self.depth_sum += depth as u64;
+        self.info.depth_max = self.info.depth_max.max(depth);
+    }
+    
+    //Updates the metrics in Info that are derived from other metrics.
+    fn statistics(&mut self) {
+        #[inline]
+        fn ratio(a: f64, b: f64) -> f32 {
+            if b > 0.0 {(a/b) as f32} else {0.0}
+        }
+        
+        let info = &mut self.info;
+        info.bytes = self.stack.len() * (std::mem::size_of::<Node<P,A>>() + std::mem::size_of::<f32>())
+            + self.map.capacity() * std::mem::size_of::<((u64,bool),(usize,u8))>()
+            + self.states.capacity() * std::mem::size_of::<(usize,S)>()
+            + self.mast.capacity() * std::mem::size_of::<((bool,u64),(f32,u32))>()
+            + self.actions.capacity() * std::mem::size_of::<A>()
+            + self.weights.capacity() * std::mem::size_of::<f32>();
+        info.depth_avg = ratio(self.depth_sum as f64,self.iterations as f64);
+        info.rate = ratio(self.iterations as f64,self.elapsed.as_secs_f64());
+        info.hit_rate = ratio(info.transpose as f64,info.lookups as f64);
+        
+        //The branching factor b of a uniform tree with b^depth branch nodes
+        info.branching = if info.depth_avg > 0.0 {
+            (info.branch as f32).powf(1.0/info.depth_avg)
         } else {
-            let root = self.root;
-            for _ in 0..n {
-                self.go(&root,0);
+            0.0
+        };
+    }
+    
+    fn expand_root(&mut self) {
+        let mut first = None;
+        self.root.actions(&mut |a| if first.is_none() {first = Some(a)});
+        
+        self.stack.push(Node::Leaf(
+            false,
+            // This action is never used, so it doesn't matter what it is
+            first.expect("should have at least one action"),
+            self.root.player(),
+            0.5,
+            1
+        ));
+        self.margins.push(0.0);
+        
+        self.info.leaf = 1;
+        self.info.nodes.push(1);
+        
+        let root = self.root.clone();
+        self.expand(&root,0);
+    }
+    
+    fn expand(&mut self, state: &S, index: usize) {
+        if let Node::Leaf(s,a,p,w,n) = self.stack[index] {
+            let c = self.stack.len();
+            let t = self.symmetry(state);
+            let stack = &mut self.stack;
+            let margins = &mut self.margins;
+            let info = &mut self.info;
+            
+            state.actions(&mut |a| {
+                //Store actions in the canonical orientation of the game state
+                stack.push(Node::Unknown(true,state.transform(a,t)));
+                margins.push(0.0);
+                info.unknown += 1;
+            });
+            
+            if let Some(Node::Unknown(_,a)) = self.stack.pop() {
+                self.stack.push(Node::Unknown(false,a));
+            }
+            
+            //The path holds the ancestors of the node, so its length is the depth of the node
+            let depth = self.path.len() + 1;
+            let nodes = &mut self.info.nodes;
+            if nodes.len() <= depth {
+                nodes.resize(depth + 1,0);
             }
+            nodes[depth] += (self.stack.len() - c) as u32;
             
-            self.info.bytes = self.stack.len() * std::mem::size_of::<Node<P,A>>();
+            self.stack[index] = Node::Branch(s,a,p,w,n,c);
+            self.info.leaf -= 1;
+            self.info.branch += 1;
+        } else {
+            debug_assert!(false,"only leaf nodes can be expanded");
         }
     }
     
-    fn uct(&self,index: usize, player: P, nt: u32) -> (bool,A,f32) {
+    //Resolves an unknown node into a terminal, leaf, or transposition.
+    fn discover(&mut self, state: &S, index: usize, s: bool, a: A) {
+        if self.use_transposition {
+            self.info.lookups += 1;
+            let (h,t1) = if self.use_symmetry {state.canonical()} else {(state.hash(),0)};
+            let key = (h, state.player() == self.root.player());
+            match self.map.get(&key) {
+                //Symmetric game states are not equal, so they can only be verified when they have the same orientation
+                Some(&(u,t0)) if (t0 != t1) || self.verified(state,u) => {
+                    self.stack[index] = Node::Transpose(s,a,u,0.0,0);
+                    self.info.unknown -= 1;
+                    self.info.transpose += 1;
+                    return;
+                },
+                Some(_) => {
+                    // hash collision with a different game state, so do not share the node
+                    self.info.collisions += 1;
+                },
+                None => {
+                    self.map.insert(key, (index,t1));
+                    if self.verify.is_some() {
+                        self.states.insert(index, state.clone());
+                    }
+                }
+            }
+        }
+        
+        let p = state.player();
+        if let Some(result) = state.gameover() {   
+            let m = state.score();
+            self.margins[index] = m;
+            self.stack[index] = Node::Terminal(s,a,p,self.blend(self.value(&result,p),m),0);
+            self.info.unknown -= 1;
+            self.info.terminal += 1;
+        } else {
+            self.stack[index] = Node::Leaf(s,a,p,0.0,0);
+            self.info.unknown -= 1;
+            self.info.leaf += 1;
+        }
+    }
+    
+    //Index of the symmetry that maps the game state onto the canonical orientation used by the tree
+    #[inline]
+    pub(crate) fn symmetry(&self, state: &S) -> u8 {
+        if self.use_symmetry {state.canonical().1} else {0}
+    }
+    
+    //Maps an action from the canonical orientation of the tree to the orientation of the game state
+    #[inline]
+    fn orient(&self, state: &S, action: A) -> A {
+        if self.use_symmetry {state.inverse(action,self.symmetry(state))} else {action}
+    }
+    
+    fn verified(&self, state: &S, index: usize) -> bool {
+        match self.verify {
+            Some(eq) => self.states.get(&index).is_some_and(|other| eq(state,other)),
+            None => true,
+        }
+    }
+    
+    //Value of a game result for player. Draws are valued relative to the player at the root (see with_contempt).
+    #[inline]
+    fn value(&self, result: &GameResult, player: P) -> f32 {
+        match result {
+            GameResult::Win => 1.0,
+            GameResult::Lose => 0.0,
+            GameResult::Draw => if player == self.root.player() {self.draw} else {1.0 - self.draw},
+        }
+    }
+    
+    //Value of a game over state with the given result value and score margin.
+    #[inline]
+    fn blend(&self, v: f32, m: f32) -> f32 {
+        (1.0 - self.score_weight)*v + self.score_weight*0.5*(m + 1.0)
+    }
+    
+    //Value of an unvisited action from the perspective of a parent node with value w and visits n.
+    fn first_play(&self, w: f32, n: u32) -> f32 {
+        match self.fpu {
+            FirstPlayUrgency::Infinite => f32::INFINITY,
+            FirstPlayUrgency::Value(v) => v,
+            FirstPlayUrgency::Reduction(r) => w/(n as f32) - r,
+        }
+    }
+    
+    fn choose(&self, player: P, w: f32, n: u32, c: usize) -> (A,usize) {
+        let mut selection = None;
+        let mut best = f32::NEG_INFINITY;
+        let mut sibling = Some(c);
+        let fpu = self.first_play(w,n);
+        
+        while let Some(u) = sibling {
+            let (s,a,uct) = self.uct(u,player,n,fpu);
+            if uct > best {
+                best = uct;
+                selection = Some((a,u));
+            }
+            sibling = s.then(||u+1);
+        }
+        
+        selection.expect("should find a best action")
+    }
+    
+    fn uct(&self,index: usize, player: P, nt: u32, fpu: f32) -> (bool,A,f32) {
         
         match self.stack[index] {
-            Node::Terminal(s,a,p,w) => {
+            Node::Terminal(s,a,p,w,_) => {
                 let val = if p == player {w} else {1.0 - w};
                 (s,a,val)
             },
-            Node::Unknown(_,a) => {
-                (false,a,f32::INFINITY)
+            Node::Unknown(s,a) => {
+                //No other action can be better than an infinite value, so stop searching siblings
+                (s && fpu.is_finite(),a,fpu)
             },
             Node::Leaf(s,a,p,w,n) |
             Node::Branch(s,a,p,w,n,_) => {
@@ -137,25 +520,30 @@ impl<P: Player, A: Action, S: GameState<P,A>> MCTS<P, A, S> {
                 let val = w/n + c*(nt.ln()/n).sqrt();
                 (s,a,val)
             },
-            Node::Transpose(s,a,u) => {
+            Node::Transpose(s,a,u,_,ne) => {
                 
+                //The value comes from the shared node and the exploration term comes from the edge (UCT3)
                 //Do not use recursion to allow the compiler to inline
                 let v = match self.stack[u] {
-                    Node::Terminal(_,_,p,w) => {
+                    Node::Terminal(_,_,p,w,_) => {
                         if p == player {w} else {1.0 - w}
                     },
                     Node::Unknown(_,_) => {
-                        f32::INFINITY
+                        fpu
+                    },
+                    _ if ne == 0 => {
+                        fpu
                     },
                     Node::Leaf(_,_,p,w,n) |
                     Node::Branch(_,_,p,w,n,_) => {
                         let n = n as f32;
+                        let ne = ne as f32;
                         let nt = nt as f32;
                         let w = if p == player {w} else {n - w};
                         let c = self.exploration;
-                        w/n + c*(nt.ln()/n).sqrt()
+                        w/n + c*(nt.ln()/ne).sqrt()
                     },
-                    Node::Transpose(_,_,_) => {
+                    Node::Transpose(..) => {
                         panic!("should not be possible to transpose to another transpose");
                     }
                 };
@@ -164,58 +552,265 @@ impl<P: Player, A: Action, S: GameState<P,A>> MCTS<P, A, S> {
         }
     }
     
-    fn rollout(&mut self,state: &S) -> f32 {
-        let mut sim;
-        let mut s = state;
+    //Returns the value and score margin of a leaf. The custom evaluation only estimates the win probability, so its margin is estimated from it. The state is played out in place, so it is not valid after evaluation.
+    fn evaluate(&mut self,state: &mut S) -> (f32,f32) {
+        if self.use_custom_evaluation {
+            let e = state.custom_evaluation();
+            (e,2.0*e - 1.0)
+        } else if self.lambda < 1.0 {
+            let e = state.custom_evaluation();
+            let (v,m) = self.rollout(state);
+            let l = self.lambda;
+            ((1.0 - l)*e + l*v,(1.0 - l)*(2.0*e - 1.0) + l*m)
+        } else {
+            self.rollout(state)
+        }
+    }
+    
+    fn rollout(&mut self,state: &mut S) -> (f32,f32) {
+        let p = state.player();
+        let steps = self.info.steps;
+        let (v,m) = self.playout(state);
+        
+        let length = ((self.info.steps - steps) as usize).min(Info::MAX_ROLLOUT);
+        let lengths = &mut self.info.rollouts;
+        if lengths.len() <= length {
+            lengths.resize(length + 1,0);
+        }
+        lengths[length] += 1;
+        
+        if let RolloutPolicy::Mast(_) = self.policy {
+            for i in 0..self.trace.len() {
+                let (q,a) = self.trace[i];
+                self.learn(q,a,if q == p {v} else {1.0 - v});
+            }
+            self.trace.clear();
+        }
+        
+        (v,m)
+    }
+    
+    fn playout(&mut self,s: &mut S) -> (f32,f32) {
         let p = s.player();
+        let mut depth = 0;
         
         loop {
             if let Some(result) = s.gameover() {
                 let side = s.player() == p;
-                let v = result.value();
-                return if side {v} else {1.0 - v}
+                let m = s.score();
+                let v = self.blend(self.value(&result,s.player()),m);
+                return if side {(v,m)} else {(1.0 - v,-m)}
             }
             
-            self.actions.clear();
-            s.actions(&mut |a|{
-                self.actions.push(a);
-            });
+            if depth == self.depth {
+                let side = s.player() == p;
+                let v = s.custom_evaluation();
+                let m = 2.0*v - 1.0;
+                return if side {(v,m)} else {(1.0 - v,-m)}
+            }
+            depth += 1;
+            self.info.steps += 1;
+            
+            let action = self.rollout_action(s);
+            if let RolloutPolicy::Mast(_) = self.policy {
+                self.trace.push((s.player(),action));
+            }
             
-            //use rejection sampling to choose a random action
-            let max = self.actions.len();
-            let mask = max.next_power_of_two() - 1;
-            loop {
-                let r = (self.rand.next_u64() as usize) & mask;
-                if r < max {
-                    sim = s.make(self.actions[r]);
-                    break;
+            s.apply(action);
+        }
+    }
+    
+    //Updates the MAST average for the action played by player. The value v is from the perspective of player.
+    fn learn(&mut self, player: P, action: A, v: f32) {
+        if let Some(k) = action.key() {
+            let side = player == self.root.player();
+            let (w,n) = self.mast.entry((side,k)).or_insert((0.0,0));
+            *w += v;
+            *n += 1;
+        }
+    }
+    
+    fn rollout_action(&mut self, s: &S) -> A {
+        if (self.policy == RolloutPolicy::Custom) && (self.decisive == DecisiveMoves::Off) {
+            let rand = &mut self.rand;
+            if let Some(a) = s.rollout_action(&mut || rand.next_u64()) {
+                return a;
+            }
+        }
+        
+        self.actions.clear();
+        s.actions(&mut |a|{
+            self.actions.push(a);
+        });
+        
+        if self.decisive != DecisiveMoves::Off {
+            if let Some(a) = self.lookahead(s) {
+                return a;
+            }
+        }
+        
+        let i = match self.policy {
+            RolloutPolicy::Uniform |
+            RolloutPolicy::Custom => {
+                self.uniform(self.actions.len())
+            },
+            RolloutPolicy::EpsilonGreedy(e) => {
+                if self.rand.gen::<f32>() < e {
+                    self.uniform(self.actions.len())
+                } else {
+                    self.greedy(s)
+                }
+            },
+            RolloutPolicy::Softmax(t) => {
+                self.weights.clear();
+                for &a in self.actions.iter() {
+                    self.weights.push(s.rollout_weight(a));
+                }
+                self.gibbs(t)
+            },
+            RolloutPolicy::Mast(t) => {
+                let side = s.player() == self.root.player();
+                self.weights.clear();
+                for &a in self.actions.iter() {
+                    // actions that have never been played are optimistically valued as wins
+                    let q = a.key()
+                        .and_then(|k| self.mast.get(&(side,k)))
+                        .map_or(1.0,|&(w,n)| w/(n as f32));
+                    self.weights.push(q);
+                }
+                self.gibbs(t)
+            }
+        };
+        
+        self.actions[i]
+    }
+    
+    //Returns an action that wins immediately if there is one. With anti-decisive lookahead, actions that allow the opponent to win immediately are also removed from the list of actions unless all of them do.
+    fn lookahead(&mut self, s: &S) -> Option<A> {
+        #[inline]
+        fn wins<P: Player, A: Action, S: GameState<P,A>>(p: P, next: &S) -> bool {
+            match next.gameover() {
+                Some(GameResult::Win) => next.player() == p,
+                Some(GameResult::Lose) => next.player() != p,
+                _ => false,
+            }
+        }
+        
+        let p = s.player();
+        for &a in self.actions.iter() {
+            self.info.lookahead += 1;
+            if wins(p,&s.make(a)) {
+                return Some(a);
+            }
+        }
+        
+        if self.decisive == DecisiveMoves::AntiDecisive {
+            let mut safe = 0;
+            for i in 0..self.actions.len() {
+                let a = self.actions[i];
+                let next = s.make(a);
+                let q = next.player();
+                let mut threat = false;
+                let mut count = 1;
+                
+                if next.gameover().is_none() && (q != p) {
+                    next.actions(&mut |r| {
+                        if !threat {
+                            count += 1;
+                            threat = wins(q,&next.make(r));
+                        }
+                    });
+                }
+                
+                self.info.lookahead += count;
+                if !threat {
+                    self.actions[safe] = a;
+                    safe += 1;
                 }
             }
             
-            s = &sim;
+            if safe > 0 {
+                self.actions.truncate(safe);
+            }
         }
+        
+        None
     }
     
-    fn go(&mut self,state: &S, index: usize) -> f32 {
+    fn uniform(&mut self, max: usize) -> usize {
+        //use rejection sampling to choose a random action
+        let mask = max.next_power_of_two() - 1;
+        loop {
+            let r = (self.rand.next_u64() as usize) & mask;
+            if r < max {
+                return r;
+            }
+        }
+    }
+    
+    fn greedy(&mut self, s: &S) -> usize {
+        let mut best = 0;
+        let mut max = f32::NEG_INFINITY;
+        let mut ties = 0;
+        
+        for i in 0..self.actions.len() {
+            let w = s.rollout_weight(self.actions[i]);
+            if w > max {
+                max = w;
+                best = i;
+                ties = 1;
+            } else if w == max {
+                //reservoir sampling breaks ties uniformly
+                ties += 1;
+                if self.uniform(ties) == 0 {
+                    best = i;
+                }
+            }
+        }
+        
+        best
+    }
+    
+    //Chooses an index with probability proportional to exp(weight/temperature).
+    fn gibbs(&mut self, temperature: f32) -> usize {
+        let max = self.weights.iter().fold(f32::NEG_INFINITY,|m,&w| m.max(w));
+        
+        let mut sum = 0.0;
+        for w in self.weights.iter_mut() {
+            *w = ((*w - max)/temperature).exp();
+            sum += *w;
+        }
+        
+        let mut r = sum*self.rand.gen::<f32>();
+        for (i,w) in self.weights.iter().enumerate() {
+            if r < *w {
+                return i;
+            }
+            r -= w;
+        }
+        
+        self.weights.len() - 1
+    }
+    
+    //Returns the value and score margin of the node from the perspective of its player.
+    fn go(&mut self,state: &mut S, index: usize) -> (f32,f32) {
         match self.stack[index] {
             Node::Branch(s,a,player,w,n,c) => {
-                let mut selection = None;
-                let mut best = -1.0;
-                let mut sibling = Some(c);
-                
-                while let Some(u) = sibling {
-                    let (s,a,uct) = self.uct(u,player,n);
-                    if uct > best {
-                        best = uct;
-                        selection = Some((a,u));
-                    }
-                    sibling = s.then(||u+1);
-                }
-                let (action,next_index) = selection.expect("should find a best action");
-                let next = state.make(action);
-                let v = self.go(&next,next_index);
+                let (action,next_index) = self.choose(player,w,n,c);
+                let action = self.orient(state,action);
+                state.apply(action);
+                let q = state.player();
+                self.path.push(index);
+                self.reach = self.reach.max(self.path.len());
+                let (v,m) = self.go(state,next_index);
+                self.path.pop();
 
-                let v = if next.player() == player {v} else {1.0 - v};
+                let (v,m) = if q == player {(v,m)} else {(1.0 - v,-m)};
+                self.margins[index] += m;
+                if let RolloutPolicy::Mast(_) = self.policy {
+                    self.learn(player,action,v);
+                }
+                
                 let w = w + v;
                 let n = n + 1;
                 self.stack[index] = Node::Branch(s,a,player,w,n,c);
@@ -225,70 +820,134 @@ impl<P: Player, A: Action, S: GameState<P,A>> MCTS<P, A, S> {
                     self.info.n = n;
                 }
                 
-                v
+                (v,m)
             },
             Node::Leaf(s,a,p,w,n) => {
                 if n > self.expansion {
-                    let c = self.stack.len();
-                    
-                    state.actions(&mut |a| {
-                        self.stack.push(Node::Unknown(true,a));
-                        self.info.unknown += 1;
-                    });
-                    
-                    
-                    if let Some(Node::Unknown(_,a)) = self.stack.pop() {
-                        self.stack.push(Node::Unknown(false,a));
-                    }
-                    
-                    self.stack[index] = Node::Branch(s,a,p,w,n,c);
-                    self.info.leaf -= 1;
-                    self.info.branch += 1;
+                    self.expand(state,index);
                     self.go(state,index)
                 } else {
-                    let v = if self.use_custom_evaluation {
-                        state.custom_evaluation()
-                    } else {
-                        self.rollout(state)
-                    };
+                    let (v,m) = self.evaluate(state);
                     self.stack[index] = Node::Leaf(s,a,p,w + v,n + 1);
-                    v
+                    self.margins[index] += m;
+                    (v,m)
                 }
             },
-            Node::Terminal(_,_,_,w) => {
-                w
+            Node::Terminal(s,a,p,w,n) => {
+                self.stack[index] = Node::Terminal(s,a,p,w,n + 1);
+                (w,self.margins[index])
             },
             Node::Unknown(s,a) => {
-                
-                if self.use_transposition {
-                    let h = state.hash();
-                    if let Some(&u) = self.map.get(&h) {
-                        self.stack[index] = Node::Transpose(s,a,u);
-                        self.info.unknown -= 1;
-                        self.info.transpose += 1;
-                        return self.go(state,u);
-                    } else {
-                        self.map.insert(h, index);
-                    }
+                self.discover(state,index,s,a);
+                self.go(state,index)
+            },
+            Node::Transpose(s,a,u,w,n) => {
+                if self.path.contains(&u) {
+                    //The game has returned to a state that is still being searched, so score the cycle as a draw
+                    let v = self.value(&GameResult::Draw,state.player());
+                    return (self.blend(v,0.0),0.0);
                 }
                 
-                let p = state.player();
-                if let Some(result) = state.gameover() {   
-                    self.stack[index] = Node::Terminal(s,a,p,result.value());
-                    self.info.unknown -= 1;
-                    self.info.terminal += 1;
+                let (v,m) = self.go(state,u);
+                self.stack[index] = Node::Transpose(s,a,u,w + v,n + 1);
+                self.margins[index] += m;
+                (v,m)
+            }
+        }
+    }
+    
+    //Descends the tree the same way as go, but stops at a leaf node instead of evaluating it. A virtual loss is applied to each node along the way and recorded in the path so that it can be corrected by backup.
+    fn select(&mut self, state: &mut S, index: usize, parent: Option<P>, path: &mut Vec<(usize,f32)>) -> Selection<P,S> {
+        #[inline]
+        fn loss<P: Player>(parent: Option<P>, p: P) -> f32 {
+            match parent {
+                Some(q) if q == p => 0.0,
+                Some(_) => 1.0,
+                None => 0.5,
+            }
+        }
+        
+        match self.stack[index] {
+            Node::Branch(s,a,player,w,n,c) => {
+                let l = loss(parent,player);
+                self.stack[index] = Node::Branch(s,a,player,w + l,n + 1,c);
+                path.push((index,l));
+                
+                let (action,next_index) = self.choose(player,w,n,c);
+                let action = self.orient(state,action);
+                state.apply(action);
+                self.path.push(index);
+                self.reach = self.reach.max(self.path.len());
+                let selection = self.select(state,next_index,Some(player),path);
+                self.path.pop();
+                selection
+            },
+            Node::Leaf(s,a,p,w,n) => {
+                if n > self.expansion {
+                    self.expand(state,index);
+                    self.select(state,index,parent,path)
                 } else {
-                    
-                    self.stack[index] = Node::Leaf(s,a,p,0.0,0);
-                    self.info.unknown -= 1;
-                    self.info.leaf += 1;
+                    let l = loss(parent,p);
+                    self.stack[index] = Node::Leaf(s,a,p,w + l,n + 1);
+                    path.push((index,l));
+                    Selection::Pending(state.clone())
                 }
-                
-                self.go(state,index)
             },
-            Node::Transpose(_,_,u) => {
-                self.go(state,u)
+            Node::Terminal(s,a,p,w,n) => {
+                self.stack[index] = Node::Terminal(s,a,p,w,n + 1);
+                Selection::Done(p,w,self.margins[index])
+            },
+            Node::Unknown(s,a) => {
+                self.discover(state,index,s,a);
+                self.select(state,index,parent,path)
+            },
+            Node::Transpose(s,a,u,w,n) => {
+                let p = state.player();
+                if path.iter().any(|&(i,_)| i == u) {
+                    let v = self.value(&GameResult::Draw,p);
+                    return Selection::Done(p,self.blend(v,0.0),0.0);
+                }
+                
+                let l = loss(parent,p);
+                self.stack[index] = Node::Transpose(s,a,u,w + l,n + 1);
+                path.push((index,l));
+                self.select(state,u,parent,path)
             }
         }
     }
-}
\ No newline at end of file
+    
+    //Replaces the virtual loss along a path with the value v and adds the score margin m. Both are from the perspective of player.
+    fn backup(&mut self, path: &[(usize,f32)], player: P, v: f32, m: f32) {
+        for &(index,l) in path {
+            match self.stack[index] {
+                Node::Leaf(s,a,p,w,n) => {
+                    let (v,m) = if p == player {(v,m)} else {(1.0 - v,-m)};
+                    self.stack[index] = Node::Leaf(s,a,p,w + v - l,n);
+                    self.margins[index] += m;
+                },
+                Node::Branch(s,a,p,w,n,c) => {
+                    let (v,m) = if p == player {(v,m)} else {(1.0 - v,-m)};
+                    self.margins[index] += m;
+                    let w = w + v - l;
+                    self.stack[index] = Node::Branch(s,a,p,w,n,c);
+                    
+                    if index == 0 {
+                        self.info.q = w/(n as f32);
+                        self.info.n = n;
+                    }
+                },
+                Node::Transpose(s,a,u,w,n) => {
+                    let p = match self.stack[u] {
+                        Node::Leaf(_,_,p,_,_) |
+                        Node::Branch(_,_,p,_,_,_) => p,
+                        _ => player,
+                    };
+                    let (v,m) = if p == player {(v,m)} else {(1.0 - v,-m)};
+                    self.stack[index] = Node::Transpose(s,a,u,w + v - l,n);
+                    self.margins[index] += m;
+                },
+                _ => debug_assert!(false,"only leaf, branch, and transpose nodes are visited")
+            }
+        }
+    }
+}
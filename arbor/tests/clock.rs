@@ -0,0 +1,47 @@
+use arbor::*;
+use std::time::Duration;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+#[test]
+fn clock_limits() {
+    let manager = TimeManager::new(Clock {remaining: ms(60050), increment: ms(0), moves: 30});
+    assert!(manager.soft == ms(2000));
+    assert!(manager.hard == ms(6000));
+    
+    // the increment is mostly spent on each move
+    let manager = TimeManager::new(Clock {remaining: ms(60050), increment: ms(1000), moves: 30});
+    assert!(manager.soft == ms(2750));
+    
+    // never use more than half of the remaining time
+    let manager = TimeManager::new(Clock {remaining: ms(100), increment: ms(0), moves: 2});
+    assert!(manager.soft == ms(45));
+    assert!(manager.hard == ms(45));
+    
+    let manager = TimeManager::new(Clock {remaining: ms(0), increment: ms(0), moves: 0});
+    assert!(manager.hard == ms(0));
+}
+
+#[test]
+fn clock_stop() {
+    let clock = Clock {remaining: ms(60050), increment: ms(0), moves: 30};
+    
+    // a lead larger than the remaining iterations before the soft limit dominates
+    let mut manager = TimeManager::new(clock);
+    assert!(!manager.stop(ms(1000),0,500,1000.0));
+    assert!(manager.stop(ms(1000),0,1500,1000.0));
+    
+    // the leader changed recently, so the search is extended past the soft limit
+    let mut manager = TimeManager::new(clock);
+    assert!(!manager.stop(ms(1500),0,0,1000.0));
+    assert!(!manager.stop(ms(2000),1,0,1000.0));
+    assert!(!manager.stop(ms(2500),1,0,1000.0));
+    assert!(manager.stop(ms(3000),1,0,1000.0));
+    
+    // the search never runs past the hard limit
+    let mut manager = TimeManager::new(clock);
+    assert!(!manager.stop(ms(5900),0,0,1000.0));
+    assert!(manager.stop(ms(6000),1,0,1000.0));
+}
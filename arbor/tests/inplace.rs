@@ -0,0 +1,83 @@
+use arbor::*;
+use std::fmt;
+
+// Nim with the piles stored on the heap, so the game state can be cloned but not copied. The player that takes the last object wins.
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+struct Take(usize,u32);
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+enum Side {A,B}
+
+#[derive(Clone,Debug,PartialEq)]
+struct Nim {
+    piles: Vec<u32>,
+    side: Side,
+}
+
+impl fmt::Display for Nim {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"{:?} to take from {:?}",self.side,self.piles)
+    }
+}
+
+impl Action for Take {}
+impl Player for Side {}
+
+impl GameState<Side,Take> for Nim {
+    fn actions<F>(&self,f: &mut F) where F: FnMut(Take) {
+        for (i,&pile) in self.piles.iter().enumerate() {
+            for n in 1..=pile {
+                f(Take(i,n));
+            }
+        }
+    }
+
+    fn make(&self,take: Take) -> Self {
+        let mut next = self.clone();
+        next.apply(take);
+        next
+    }
+
+    fn apply(&mut self,Take(i,n): Take) {
+        self.piles[i] -= n;
+        self.side = if self.side == Side::A {Side::B} else {Side::A};
+    }
+
+    fn gameover(&self) -> Option<GameResult> {
+        if self.piles.iter().all(|&pile| pile == 0) {Some(GameResult::Lose)} else {None}
+    }
+
+    fn player(&self) -> Side {
+        self.side
+    }
+
+    fn hash(&self) -> u64 {
+        self.piles.iter().fold(0,|h,&pile| (h << 8) | (pile as u64))
+    }
+}
+
+#[test]
+fn inplace_best() {
+    let game = Nim {piles: vec![1,2,4], side: Side::A};
+    let mut mcts = MCTS::new(game.clone());
+    mcts.ponder(10000);
+    assert!(mcts.best() == Some(Take(2,1)));
+
+    let mut mcts = MCTS::new(game).with_transposition();
+    mcts.ponder(10000);
+    assert!(mcts.best() == Some(Take(2,1)));
+}
+
+#[test]
+fn inplace_batch() {
+    let game = Nim {piles: vec![3,5], side: Side::A};
+    let mut mcts = MCTS::new(game);
+    mcts.ponder_batch(10000,&mut |states,values| {
+        for (state,value) in states.iter().zip(values.iter_mut()) {
+            // piles with the same size are a loss for the side to play
+            *value = if state.piles[0] == state.piles[1] {0.0} else {1.0};
+        }
+    });
+    assert!(mcts.best() == Some(Take(1,2)));
+}
@@ -0,0 +1,98 @@
+use arbor::*;
+use std::fmt;
+
+// The first player picks a bonus and the second player then picks a penalty. Every game is won by the first player, but the margin depends on both choices.
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+struct Pick(u8);
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+enum Side {A,B}
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+struct Margin {
+    picks: [Option<u8>;2],
+}
+
+impl fmt::Display for Margin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"{:?}",self.picks)
+    }
+}
+
+impl Action for Pick {}
+impl Player for Side {}
+
+impl Margin {
+    fn margin(&self) -> f32 {
+        let bonus = self.picks[0].unwrap_or(0) as f32;
+        let penalty = self.picks[1].unwrap_or(0) as f32;
+        (2.0 + bonus - penalty)/8.0
+    }
+}
+
+impl GameState<Side,Pick> for Margin {
+    fn actions<F>(&self,f: &mut F) where F: FnMut(Pick) {
+        for i in 0..3 {
+            f(Pick(i));
+        }
+    }
+
+    fn make(&self,Pick(i): Pick) -> Self {
+        let mut next = *self;
+        if self.picks[0].is_none() {
+            next.picks[0] = Some(i);
+        } else {
+            next.picks[1] = Some(i);
+        }
+        next
+    }
+
+    fn gameover(&self) -> Option<GameResult> {
+        // the first player always wins
+        self.picks[1].and(Some(GameResult::Win))
+    }
+
+    fn player(&self) -> Side {
+        if self.picks[0].is_none() || self.picks[1].is_some() {Side::A} else {Side::B}
+    }
+
+    fn score(&self) -> f32 {
+        if self.player() == Side::A {self.margin()} else {-self.margin()}
+    }
+}
+
+#[test]
+fn score_margin() {
+    let game = Margin {picks: [None,None]};
+    let mut mcts = MCTS::new(game).with_score_weight(0.5);
+    mcts.ponder(5000);
+    assert!(mcts.best() == Some(Pick(2)));
+    
+    let mut margins = Vec::new();
+    mcts.ply(&mut |(a,w,_s,m)| {
+        assert!(w > 0.5);
+        margins.push((a,m));
+    });
+    
+    // the second player always picks the largest penalty, so the margins are known
+    for (Pick(i),m) in margins {
+        let expected = (i as f32)/8.0;
+        assert!((m - expected).abs() < 0.05,"{} != {}",m,expected);
+    }
+}
+
+#[test]
+fn score_margin_loser() {
+    let game = Margin {picks: [Some(0),None]};
+    let mut mcts = MCTS::new(game).with_score_weight(0.5);
+    mcts.ponder(1000);
+    assert!(mcts.best() == Some(Pick(2)));
+    
+    mcts.ply(&mut |(Pick(i),w,_s,m)| {
+        // every action loses, so only the margin differs
+        let expected = -(2.0 - (i as f32))/8.0;
+        assert!(w < 0.5);
+        assert!(m == expected,"{} != {}",m,expected);
+    });
+}
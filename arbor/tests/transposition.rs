@@ -0,0 +1,76 @@
+use arbor::*;
+use std::fmt;
+
+// A token moves around a ring of six spaces. The player that moves the token onto space 3 wins. Positions repeat, so the game has cycles.
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+enum Step {Left,Right}
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+enum Side {A,B}
+
+#[derive(Copy,Clone,Debug,PartialEq)]
+struct Ring {
+    pos: u64,
+    side: Side,
+    collide: bool,
+}
+
+impl fmt::Display for Ring {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f,"{:?} at {}",self.side,self.pos)
+    }
+}
+
+impl Action for Step {}
+impl Player for Side {}
+
+impl GameState<Side,Step> for Ring {
+    fn actions<F>(&self,f: &mut F) where F: FnMut(Step) {
+        f(Step::Left);
+        f(Step::Right);
+    }
+
+    fn make(&self,step: Step) -> Self {
+        let pos = match step {
+            Step::Left => (self.pos + 5) % 6,
+            Step::Right => (self.pos + 1) % 6,
+        };
+        let side = if self.side == Side::A {Side::B} else {Side::A};
+        Ring {pos, side, collide: self.collide}
+    }
+
+    fn gameover(&self) -> Option<GameResult> {
+        if self.pos == 3 {Some(GameResult::Lose)} else {None}
+    }
+
+    fn player(&self) -> Side {
+        self.side
+    }
+
+    fn hash(&self) -> u64 {
+        if self.collide {self.pos % 2} else {self.pos}
+    }
+}
+
+#[test]
+fn transposition_cycles() {
+    let game = Ring {pos: 0, side: Side::A, collide: false};
+    let mut mcts = MCTS::new(game).with_transposition();
+    mcts.ponder(5000);
+    assert!(mcts.info.transpose > 0);
+
+    let game = Ring {pos: 2, side: Side::A, collide: false};
+    let mut mcts = MCTS::new(game).with_transposition();
+    mcts.ponder(5000);
+    assert!(mcts.best() == Some(Step::Right));
+}
+
+#[test]
+fn transposition_verified_collisions() {
+    let game = Ring {pos: 2, side: Side::A, collide: true};
+    let mut mcts = MCTS::new(game).with_verified_transposition();
+    mcts.ponder(5000);
+    assert!(mcts.info.collisions > 0);
+    assert!(mcts.best() == Some(Step::Right));
+}
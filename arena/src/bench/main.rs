@@ -0,0 +1,30 @@
+mod measure;
+use std::env;
+use self::measure::{Config,measure};
+
+// usage: bench '{"games":["reversi","connect4"],"millis":1000,"iterations":100000}'
+// prints a json report for each game, so the results of two builds can be compared
+fn main() {
+    let config: Config = match env::args().nth(1) {
+        Some(arg) => serde_json::from_str(&arg).expect("benchmark config is not valid"),
+        None => Config::default(),
+    };
+
+    let mut reports = Vec::new();
+    for game in config.games.iter() {
+        eprintln!("{}",game);
+        let report = match game.as_str() {
+            "reversi" => measure(game,reversi::Reversi::new,&config),
+            "connect4" => measure(game,connect4::connect4::Connect4::new,&config),
+            "mancala" => measure(game,mancala::Mancala::new,&config),
+            "tictactoe" => measure(game,tictactoe::TicTacToe::new,&config),
+            game => panic!("unknown game {}",game),
+        };
+        reports.push(report);
+    }
+
+    println!("{}",serde_json::to_string_pretty(&reports).expect("reports should serialize"));
+}
+
+#[cfg(test)]
+mod test;
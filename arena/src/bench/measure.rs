@@ -0,0 +1,138 @@
+use arbor::*;
+use serde::{Deserialize,Serialize};
+use std::hint::black_box;
+use std::time::{Duration,Instant};
+
+// the benchmarks to run read from json
+#[derive(Debug,Clone,Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // reversi, connect4, mancala, or tictactoe
+    pub games: Vec<String>,
+    // milliseconds spent on each throughput measurement
+    pub millis: u64,
+    // iterations of each search
+    pub iterations: usize,
+    pub seed: u64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            games: ["reversi","connect4","mancala","tictactoe"].iter().map(|g| String::from(*g)).collect(),
+            millis: 1000,
+            iterations: 100000,
+            seed: 0,
+        }
+    }
+}
+
+// the throughput of a search with and without transposition
+#[derive(Debug,Default,Clone,Serialize)]
+pub struct Search {
+    pub iterations_per_sec: f64,
+    // memory used by the search tree scaled to a million iterations
+    pub bytes_per_million: f64,
+    pub nodes: u32,
+}
+
+#[derive(Debug,Default,Clone,Serialize)]
+pub struct Report {
+    pub game: String,
+    pub make_per_sec: f64,
+    pub actions_per_sec: f64,
+    // random playouts of the search from the children of the starting game state
+    pub rollouts_per_sec: f64,
+    // actions played in those playouts
+    pub rollout_steps_per_sec: f64,
+    pub search: Search,
+    pub transposition: Search,
+}
+
+// game states that are not over from random games
+pub fn positions<P,A,S>(new: fn() -> S, count: usize, seed: u64) -> Vec<S>
+where P: Player, A: Action, S: GameState<P,A> {
+    let mut random = RandomAgent::new(seed);
+    let mut states = Vec::with_capacity(count);
+    while states.len() < count {
+        let mut state = new();
+        while state.gameover().is_none() && states.len() < count {
+            states.push(state.clone());
+            let action = Agent::<P,A,S>::choose(&mut random,&state).expect("random agent should find an action");
+            state.apply(action);
+        }
+    }
+    states
+}
+
+// runs the work until the duration has passed and returns the number of operations per second, where the work returns the number of operations it did
+fn rate<F: FnMut() -> u64>(duration: Duration, mut work: F) -> f64 {
+    let start = Instant::now();
+    let mut operations = 0;
+    while start.elapsed() < duration {
+        operations += work();
+    }
+    (operations as f64)/start.elapsed().as_secs_f64()
+}
+
+fn search<P,A,S>(mcts: MCTS<P,A,S>, iterations: usize) -> Search
+where P: Player, A: Action, S: GameState<P,A> {
+    let mut mcts = mcts;
+    let start = Instant::now();
+    mcts.ponder(iterations);
+    let seconds = start.elapsed().as_secs_f64();
+    let info = &mcts.info;
+    Search {
+        iterations_per_sec: (info.n as f64)/seconds,
+        bytes_per_million: (info.bytes as f64)*1e6/(info.n.max(1) as f64),
+        nodes: info.branch + info.leaf + info.terminal + info.unknown + info.transpose,
+    }
+}
+
+pub fn measure<P,A,S>(game: &str, new: fn() -> S, config: &Config) -> Report
+where P: Player, A: Action, S: GameState<P,A> {
+    let duration = Duration::from_millis(config.millis);
+    let states = positions(new,1000,config.seed);
+
+    let actions_per_sec = rate(duration,|| {
+        let mut n = 0;
+        for state in states.iter() {
+            state.actions(&mut |a| {
+                black_box(a);
+            });
+            n += 1;
+        }
+        n
+    });
+
+    let mut moves = Vec::new();
+    for state in states.iter() {
+        state.actions(&mut |a| moves.push((state,a)));
+    }
+    let make_per_sec = rate(duration,|| {
+        for &(state,a) in moves.iter() {
+            black_box(state.make(a));
+        }
+        moves.len() as u64
+    });
+
+    // leaves are never expanded, so every iteration of the search is a playout
+    let mut mcts = MCTS::new(new()).with_expansion_minimum(u32::MAX);
+    let start = Instant::now();
+    while start.elapsed() < duration {
+        mcts.ponder(100);
+    }
+    let seconds = start.elapsed().as_secs_f64();
+    let rollouts_per_sec = (mcts.info.rollouts.iter().sum::<u64>() as f64)/seconds;
+    let rollout_steps_per_sec = (mcts.info.steps as f64)/seconds;
+
+    Report {
+        game: String::from(game),
+        make_per_sec,
+        actions_per_sec,
+        rollouts_per_sec,
+        rollout_steps_per_sec,
+        search: search(MCTS::new(new()),config.iterations),
+        transposition: search(MCTS::new(new()).with_transposition(),config.iterations),
+    }
+}
@@ -0,0 +1,23 @@
+use super::measure::*;
+
+#[test]
+fn bench_measure() {
+    let states = positions(tictactoe::TicTacToe::new,100,3);
+    assert!(states.len() == 100);
+
+    let config = Config {millis: 10, iterations: 2000, ..Config::default()};
+    assert!(config.games.len() == 4);
+    let report = measure("tictactoe",tictactoe::TicTacToe::new,&config);
+    assert!(report.make_per_sec > 0.0);
+    assert!(report.actions_per_sec > 0.0);
+    assert!(report.rollouts_per_sec > 0.0);
+    assert!(report.rollout_steps_per_sec > report.rollouts_per_sec);
+    assert!(report.search.iterations_per_sec > 0.0);
+    assert!(report.search.bytes_per_million > 0.0);
+    assert!(report.search.nodes > 0);
+    assert!(report.transposition.bytes_per_million > 0.0);
+
+    let json = serde_json::to_value(&report).unwrap();
+    assert!(json["game"] == "tictactoe");
+    assert!(json["transposition"]["iterations_per_sec"].is_number());
+}
@@ -0,0 +1,144 @@
+use arbor::*;
+use serde::{Deserialize,Serialize};
+use super::stats::Sprt;
+
+// a tournament between two engines read from json
+#[derive(Debug,Clone,Deserialize)]
+#[serde(default)]
+pub struct Config {
+    // reversi, connect4, mancala, or tictactoe
+    pub game: String,
+    pub p1: Engine,
+    pub p2: Engine,
+    // each pair of games is played from the same random opening with the engines swapping sides
+    pub pairs: usize,
+    // number of random actions in each opening
+    pub start: usize,
+    // defaults to the available parallelism
+    pub threads: Option<usize>,
+    pub seed: u64,
+    // stops the tournament early when the test is decided
+    pub sprt: Option<Sprt>,
+    // appends a record of each game to this file
+    pub record: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            game: String::from("reversi"),
+            p1: Engine::default(),
+            p2: Engine::default(),
+            pairs: 100,
+            start: 4,
+            threads: None,
+            seed: 0,
+            sprt: None,
+            record: None,
+        }
+    }
+}
+
+// the search budget and MCTS builder options of an engine
+#[derive(Debug,Default,Clone,Serialize,Deserialize)]
+#[serde(default)]
+pub struct Engine {
+    pub name: String,
+    // iterations per action, 1000 unless a time is given
+    pub iterations: Option<usize>,
+    // milliseconds per action
+    pub time: Option<u64>,
+    pub exploration: Option<f32>,
+    pub expansion: Option<u32>,
+    pub fpu: Option<f32>,
+    pub transposition: bool,
+    pub symmetry: bool,
+    // off, decisive, or antidecisive
+    pub decisive: Option<String>,
+    pub rollout_depth: Option<u32>,
+    // uniform, custom, epsilon_greedy, softmax, or mast
+    pub rollout_policy: Option<String>,
+    // the epsilon of epsilon_greedy or the temperature of softmax and mast
+    pub rollout_parameter: Option<f32>,
+    // leaf nodes evaluated together with the custom evaluation instead of playouts, one at a time if not given
+    pub batch: Option<usize>,
+    pub custom_evaluation: bool,
+    pub evaluation_blend: Option<f32>,
+    pub score_weight: Option<f32>,
+    pub contempt: Option<f32>,
+    pub entropy: bool,
+}
+
+impl Engine {
+    pub fn budget(&self) -> Budget {
+        match (self.time,self.iterations) {
+            (Some(ms),_) => Budget::Time(std::time::Duration::from_millis(ms)),
+            (None,Some(n)) => Budget::Iterations(n),
+            (None,None) => Budget::Iterations(1000),
+        }
+    }
+
+    pub fn build<P: Player, A: Action, S: GameState<P,A>>(&self, state: S) -> MCTS<P,A,S> {
+        let mut mcts = MCTS::new(state);
+
+        if let Some(c) = self.exploration {
+            mcts = mcts.with_exploration(c);
+        }
+        if let Some(e) = self.expansion {
+            mcts = mcts.with_expansion_minimum(e);
+        }
+        if let Some(v) = self.fpu {
+            mcts = mcts.with_first_play_urgency(FirstPlayUrgency::Value(v));
+        }
+        if self.transposition {
+            mcts = mcts.with_transposition();
+        }
+        if self.symmetry {
+            mcts = mcts.with_symmetry();
+        }
+        if let Some(decisive) = &self.decisive {
+            let decisive = match decisive.as_str() {
+                "off" => DecisiveMoves::Off,
+                "decisive" => DecisiveMoves::Decisive,
+                "antidecisive" => DecisiveMoves::AntiDecisive,
+                _ => panic!("unknown decisive moves {}",decisive),
+            };
+            mcts = mcts.with_decisive_moves(decisive);
+        }
+        if let Some(d) = self.rollout_depth {
+            mcts = mcts.with_rollout_depth(d);
+        }
+        if let Some(policy) = &self.rollout_policy {
+            let parameter = || self.rollout_parameter.unwrap_or_else(|| panic!("rollout policy {} needs a rollout parameter",policy));
+            let policy = match policy.as_str() {
+                "uniform" => RolloutPolicy::Uniform,
+                "custom" => RolloutPolicy::Custom,
+                "epsilon_greedy" => RolloutPolicy::EpsilonGreedy(parameter()),
+                "softmax" => RolloutPolicy::Softmax(parameter()),
+                "mast" => RolloutPolicy::Mast(parameter()),
+                _ => panic!("unknown rollout policy {}",policy),
+            };
+            mcts = mcts.with_rollout_policy(policy);
+        }
+        if let Some(b) = self.batch {
+            mcts = mcts.with_batch_size(b);
+        }
+        if self.custom_evaluation {
+            mcts = mcts.with_custom_evaluation();
+        }
+        if let Some(l) = self.evaluation_blend {
+            mcts = mcts.with_evaluation_blend(l);
+        }
+        if let Some(w) = self.score_weight {
+            mcts = mcts.with_score_weight(w);
+        }
+        if let Some(d) = self.contempt {
+            mcts = mcts.with_contempt(d);
+        }
+        if self.entropy {
+            mcts = mcts.with_entropy();
+        }
+
+        mcts
+    }
+}
@@ -0,0 +1,49 @@
+mod config;
+mod stats;
+mod tournament;
+use std::env;
+use self::config::Config;
+use self::stats::Verdict;
+
+// usage: arena '{"game":"reversi","p1":{"name":"a","iterations":1000},"p2":{"name":"b","time":50},"pairs":100,"sprt":{"elo0":0,"elo1":10},"record":"games.jsonl"}'
+fn main() {
+    let arg = env::args().nth(1).expect("no tournament config");
+    let config: Config = serde_json::from_str(&arg).expect("tournament config is not valid");
+
+    let pairs = config.pairs;
+    let mut progress = |score: &stats::Score| {
+        eprint!("\r{}/{} games",score.games(),2*pairs);
+    };
+
+    let score = match config.game.as_str() {
+        "reversi" => tournament::run(reversi::Reversi::new,&config,&mut progress),
+        "connect4" => tournament::run(connect4::connect4::Connect4::new,&config,&mut progress),
+        "mancala" => tournament::run(mancala::Mancala::new,&config,&mut progress),
+        "tictactoe" => tournament::run(tictactoe::TicTacToe::new,&config,&mut progress),
+        game => panic!("unknown game {}",game),
+    };
+    eprintln!();
+
+    println!("{}: {} vs {}",config.game,config.p1.name,config.p2.name);
+    println!("games:  {}",score.games());
+    println!("w/d/l:  {}/{}/{}",score.wins,score.draws,score.losses);
+    println!("score:  {:.3}",score.mean());
+    println!("elo:    {:.1} +/- {:.1}",score.elo(),score.error());
+
+    if let Some(sprt) = config.sprt {
+        let (llr,verdict) = sprt.verdict(&score);
+        let (lower,upper) = sprt.bounds();
+        let verdict = match verdict {
+            Verdict::H0 => "H0 accepted",
+            Verdict::H1 => "H1 accepted",
+            Verdict::Continue => "inconclusive",
+        };
+        println!(
+            "sprt:   elo0 {} elo1 {} llr {:.2} ({:.2},{:.2}) {}",
+            sprt.elo0,sprt.elo1,llr,lower,upper,verdict
+        );
+    }
+}
+
+#[cfg(test)]
+mod test;
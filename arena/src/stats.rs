@@ -0,0 +1,149 @@
+use arbor::GameResult;
+use serde::Deserialize;
+
+// two sided 95% confidence
+const Z95: f64 = 1.959964;
+
+// the expected score against an opponent with the given elo difference
+pub fn expected(elo: f64) -> f64 {
+    1.0/(1.0 + 10f64.powf(-elo/400.0))
+}
+
+// the elo difference that gives the expected score
+pub fn elo(score: f64) -> f64 {
+    -400.0*(1.0/score - 1.0).log10()
+}
+
+// results of games from the perspective of the first engine
+#[derive(Debug,Default,Copy,Clone,PartialEq)]
+pub struct Score {
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+impl Score {
+    pub fn add(&mut self, result: GameResult) {
+        match result {
+            GameResult::Win => self.wins += 1,
+            GameResult::Draw => self.draws += 1,
+            GameResult::Lose => self.losses += 1,
+        }
+    }
+
+    pub fn games(&self) -> u32 {
+        self.wins + self.draws + self.losses
+    }
+
+    // fraction of the points won where a draw is worth half a point
+    pub fn mean(&self) -> f64 {
+        let n = self.games() as f64;
+        if n > 0.0 {
+            (self.wins as f64 + 0.5*self.draws as f64)/n
+        } else {
+            0.5
+        }
+    }
+
+    // variance of the points won in a single game
+    pub fn variance(&self) -> f64 {
+        let n = self.games() as f64;
+        if n > 0.0 {
+            let m = self.mean();
+            let w = self.wins as f64*(1.0 - m).powi(2);
+            let d = self.draws as f64*(0.5 - m).powi(2);
+            let l = self.losses as f64*m.powi(2);
+            (w + d + l)/n
+        } else {
+            0.0
+        }
+    }
+
+    // a perfect score has an unbounded elo difference, so one of its games is counted as a draw instead (half game correction)
+    fn corrected(&self) -> Score {
+        match *self {
+            Score {wins, draws: 0, losses: 0} if wins > 0 => Score {wins: wins - 1, draws: 1, losses: 0},
+            Score {wins: 0, draws: 0, losses} if losses > 0 => Score {wins: 0, draws: 1, losses: losses - 1},
+            score => score,
+        }
+    }
+
+    pub fn elo(&self) -> f64 {
+        elo(self.corrected().mean())
+    }
+
+    // half width of the 95% confidence interval of the elo difference
+    pub fn error(&self) -> f64 {
+        let score = self.corrected();
+        let n = score.games() as f64;
+        if n > 0.0 {
+            // the interval is kept inside the scores that have a finite elo difference
+            let m = score.mean();
+            let e = Z95*(score.variance()/n).sqrt();
+            let c = 0.25/n;
+            (elo((m + e).min(1.0 - c)) - elo((m - e).max(c)))/2.0
+        } else {
+            f64::INFINITY
+        }
+    }
+
+    // log likelihood ratio of the elo difference being elo1 rather than elo0 (normal approximation of the generalized SPRT)
+    pub fn llr(&self, elo0: f64, elo1: f64) -> f64 {
+        let n = self.games() as f64;
+        let v = self.variance();
+        if v > 0.0 {
+            let (s0,s1) = (expected(elo0),expected(elo1));
+            n*(s1 - s0)*(2.0*self.mean() - s0 - s1)/(2.0*v)
+        } else {
+            0.0
+        }
+    }
+}
+
+#[derive(Debug,Copy,Clone,PartialEq)]
+pub enum Verdict {
+    // the first engine is not stronger by elo1
+    H0,
+    // the first engine is stronger by elo1
+    H1,
+    // more games are needed
+    Continue,
+}
+
+// sequential probability ratio test of the hypotheses elo0 (H0) and elo1 (H1) with the false positive rate alpha and the false negative rate beta
+#[derive(Debug,Copy,Clone,PartialEq,Deserialize)]
+#[serde(default)]
+pub struct Sprt {
+    pub elo0: f64,
+    pub elo1: f64,
+    pub alpha: f64,
+    pub beta: f64,
+}
+
+impl Default for Sprt {
+    fn default() -> Self {
+        Sprt {elo0: 0.0, elo1: 10.0, alpha: 0.05, beta: 0.05}
+    }
+}
+
+impl Sprt {
+    // the test stops when the log likelihood ratio leaves these bounds
+    pub fn bounds(&self) -> (f64,f64) {
+        let lower = (self.beta/(1.0 - self.alpha)).ln();
+        let upper = ((1.0 - self.beta)/self.alpha).ln();
+        (lower,upper)
+    }
+
+    pub fn verdict(&self, score: &Score) -> (f64,Verdict) {
+        let llr = score.llr(self.elo0,self.elo1);
+        let (lower,upper) = self.bounds();
+        let verdict = if llr >= upper {
+            Verdict::H1
+        } else if llr <= lower {
+            Verdict::H0
+        } else {
+            Verdict::Continue
+        };
+        (llr,verdict)
+    }
+}
@@ -0,0 +1,160 @@
+use super::config::*;
+use super::stats::*;
+use super::tournament::*;
+use arbor::*;
+
+fn score(wins: u32, draws: u32, losses: u32) -> Score {
+    Score {wins, draws, losses}
+}
+
+#[test]
+fn arena_elo() {
+    assert!(elo(0.5).abs() < 1e-9);
+    assert!((elo(0.75) - 190.85).abs() < 0.01);
+    assert!((expected(-190.85) - 0.25).abs() < 1e-4);
+    for x in [-400.0,-50.0,0.0,10.0,800.0] {
+        assert!((elo(expected(x)) - x).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn arena_score() {
+    let s = score(1,0,1);
+    assert!(s.games() == 2);
+    assert!((s.mean() - 0.5).abs() < 1e-9);
+    assert!((s.variance() - 0.25).abs() < 1e-9);
+
+    let s = score(3,2,1);
+    assert!((s.mean() - 4.0/6.0).abs() < 1e-9);
+    assert!(s.elo() > 0.0);
+
+    // the error bars halve with four times as many games
+    let e1 = score(30,40,30).error();
+    let e4 = score(120,160,120).error();
+    assert!((e1/e4 - 2.0).abs() < 0.05);
+
+    // there is no information about strength in drawn games
+    let s = score(0,50,0);
+    assert!(s.variance() == 0.0);
+    assert!(s.llr(0.0,10.0) == 0.0);
+
+    assert!(Score::default().error().is_infinite());
+
+    // perfect scores are reported with a finite elo difference
+    for s in [score(10,0,0),score(0,0,10)] {
+        assert!(s.elo().is_finite());
+        assert!(s.error().is_finite() && s.error() > 0.0);
+    }
+    assert!(score(1,0,0).elo().is_finite() && score(1,0,0).error().is_finite());
+    assert!((score(10,0,0).elo() - score(9,1,0).elo()).abs() < 1e-9);
+    assert!((score(10,0,0).elo() + score(0,0,10).elo()).abs() < 1e-9);
+    assert!(score(10,0,0).elo() > 0.0);
+}
+
+#[test]
+fn arena_sprt() {
+    let sprt = Sprt::default();
+    let (lower,upper) = sprt.bounds();
+    assert!((lower + 2.944).abs() < 1e-3);
+    assert!((upper - 2.944).abs() < 1e-3);
+
+    assert!(sprt.verdict(&score(300,100,100)).1 == Verdict::H1);
+    assert!(sprt.verdict(&score(4000,2000,4000)).1 == Verdict::H0);
+    assert!(sprt.verdict(&score(200,100,200)).1 == Verdict::Continue);
+
+    // the ratio favors H1 when the score is above the midpoint of the hypotheses
+    let mid = (expected(sprt.elo0) + expected(sprt.elo1))/2.0;
+    assert!(score(52,0,48).mean() > mid);
+    assert!(score(52,0,48).llr(sprt.elo0,sprt.elo1) > 0.0);
+    assert!(score(50,0,50).llr(sprt.elo0,sprt.elo1) < 0.0);
+}
+
+#[test]
+fn arena_tournament() {
+    let (a,actions) = opening(tictactoe::TicTacToe::new,3,7);
+    let (b,_) = opening(tictactoe::TicTacToe::new,3,7);
+    assert!(a.gameover().is_none());
+    assert!(a.hash() == b.hash());
+    assert!(actions.len() == 3);
+
+    let config = Config {
+        game: String::from("tictactoe"),
+        p1: Engine {iterations: Some(200), ..Engine::default()},
+        p2: Engine {iterations: Some(200), transposition: true, ..Engine::default()},
+        pairs: 4,
+        threads: Some(2),
+        ..Config::default()
+    };
+
+    let mut updates = 0;
+    let score = run(tictactoe::TicTacToe::new,&config,&mut |_| updates += 1);
+    assert!(score.games() == 8);
+    assert!(updates == 4);
+
+    // the test stops as soon as it is decided
+    let config = Config {
+        p2: Engine {iterations: Some(1), ..Engine::default()},
+        pairs: 1000,
+        sprt: Some(Sprt {elo0: 0.0, elo1: 200.0, alpha: 0.1, beta: 0.1}),
+        ..config
+    };
+    let score = run(tictactoe::TicTacToe::new,&config,&mut |_| {});
+    assert!(score.games() < 2000);
+    assert!(config.sprt.unwrap().verdict(&score).1 == Verdict::H1);
+}
+
+#[test]
+fn arena_engine_options() {
+    let config: Config = serde_json::from_str(r#"{
+        "game": "tictactoe",
+        "p1": {"iterations": 100, "rollout_policy": "softmax", "rollout_parameter": 0.5},
+        "p2": {"iterations": 100, "batch": 8, "custom_evaluation": true},
+        "pairs": 2,
+        "threads": 1
+    }"#).unwrap();
+    assert!(config.p1.rollout_policy.as_deref() == Some("softmax"));
+    assert!(config.p2.batch == Some(8));
+    let score = run(tictactoe::TicTacToe::new,&config,&mut |_| {});
+    assert!(score.games() == 4);
+
+    // a policy with a parameter must be given one
+    let engine = Engine {rollout_policy: Some(String::from("mast")), ..Engine::default()};
+    let built = std::panic::catch_unwind(|| engine.build(tictactoe::TicTacToe::new()));
+    assert!(built.is_err());
+}
+
+#[test]
+fn arena_records() {
+    let path = std::env::temp_dir().join(format!("arena_records_{}.jsonl",std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let config = Config {
+        game: String::from("connect4"),
+        p1: Engine {name: String::from("a"), iterations: Some(50), ..Engine::default()},
+        p2: Engine {name: String::from("b"), iterations: Some(50), symmetry: true, ..Engine::default()},
+        pairs: 2,
+        start: 3,
+        threads: Some(1),
+        record: Some(path.to_string_lossy().into_owned()),
+        ..Config::default()
+    };
+    let score = run(connect4::connect4::Connect4::new,&config,&mut |_| {});
+
+    let input = std::io::BufReader::new(std::fs::File::open(&path).unwrap());
+    let records = read_records(input).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(records.len() == score.games() as usize);
+
+    // each engine plays both sides of the opening
+    for pair in records.chunks(2) {
+        assert!(pair[0].moves[..3] == pair[1].moves[..3]);
+        assert!(pair[0].p1.name == pair[1].p2.name);
+    }
+    for record in records {
+        assert!(record.game == "connect4");
+        assert!(record.opening == 3);
+        assert!(record.p1.agent["iterations"] == 50);
+        assert!(record.result != "none");
+        record.replay(connect4::connect4::Connect4::new()).expect("record should replay");
+    }
+}
+
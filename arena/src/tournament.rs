@@ -0,0 +1,161 @@
+use arbor::*;
+use std::sync::atomic::{AtomicBool,AtomicUsize,Ordering};
+use std::sync::mpsc;
+use super::config::{Config,Engine};
+use super::stats::{Score,Verdict};
+
+// plays random actions from the new game state until the opening is long enough and the game is not over
+pub fn opening<P,A,S>(new: fn() -> S, plies: usize, seed: u64) -> (S,Vec<A>)
+where P: Player, A: Action, S: GameState<P,A> {
+    let mut random = RandomAgent::new(seed);
+    loop {
+        let mut state = new();
+        let mut actions = Vec::new();
+        for _ in 0..plies {
+            if state.gameover().is_some() {
+                break;
+            }
+            let action = Agent::<P,A,S>::choose(&mut random,&state).expect("random agent should find an action");
+            state.apply(action);
+            actions.push(action);
+        }
+        if state.gameover().is_none() {
+            return (state,actions);
+        }
+    }
+}
+
+// records a game that was played from the opening, where p1 is the side to play in the new game state
+fn record<P,A,S>(new: fn() -> S, config: &Config, opening: &[A], first: &Engine, second: &Engine, outcome: &Outcome<A,S>) -> Record
+where P: Player, A: Action, S: Notation<P,A> {
+    let start = new();
+    let mut state = start.clone();
+    for &action in opening {
+        state.apply(action);
+    }
+
+    let seat = |engine: &Engine| Seat::new(&engine.name,serde_json::to_value(engine).expect("engine should serialize"));
+    let (p1,p2) = if state.player() == start.player() {(first,second)} else {(second,first)};
+    let actions: Vec<A> = opening.iter().chain(outcome.actions.iter()).copied().collect();
+
+    let mut record = Record::new(&config.game,seat(p1),seat(p2)).with_actions(&start,&actions,outcome.resigned);
+    record.opening = opening.len();
+    record
+}
+
+// an agent that searches with the engine, where a batch size evaluates leaf nodes together with the custom evaluation of the game state
+struct Contestant<'a> {
+    engine: &'a Engine,
+}
+
+impl<P: Player, A: Action, S: GameState<P,A>> Agent<P,A,S> for Contestant<'_> {
+    fn choose(&mut self, state: &S) -> Option<A> {
+        let mut mcts = self.engine.build(state.clone());
+        let batched = self.engine.batch.is_some();
+        let search = |mcts: &mut MCTS<P,A,S>, n: usize| {
+            if batched {
+                mcts.ponder_batch(n,&mut |states: &[S],values: &mut [f32]| {
+                    for (s,v) in states.iter().zip(values.iter_mut()) {
+                        *v = s.custom_evaluation();
+                    }
+                });
+            } else {
+                mcts.ponder(n);
+            }
+        };
+
+        match self.engine.budget() {
+            Budget::Time(duration) => {
+                let start = std::time::Instant::now();
+                while start.elapsed() < duration {
+                    search(&mut mcts,100);
+                }
+            },
+            Budget::Iterations(n) => search(&mut mcts,n),
+            Budget::Strength(_) => unreachable!("engines are given iterations or time"),
+        }
+        mcts.best()
+    }
+}
+
+// plays a game from each side of the opening and returns the results for the first engine along with the records of the games
+pub fn pair<P,A,S>(new: fn() -> S, config: &Config, opening: &[A]) -> [(GameResult,Record);2]
+where P: Player, A: Action, S: Notation<P,A> {
+    let (first,second) = (&config.p1,&config.p2);
+    let mut a = Contestant {engine: first};
+    let mut b = Contestant {engine: second};
+    let mut state = new();
+    for &action in opening {
+        state.apply(action);
+    }
+
+    let o1 = play(state.clone(),&mut a,&mut b,&mut |_,_| {});
+    let o2 = play(state,&mut b,&mut a,&mut |_,_| {});
+    let r2 = match o2.result {
+        GameResult::Win => GameResult::Lose,
+        GameResult::Lose => GameResult::Win,
+        GameResult::Draw => GameResult::Draw,
+    };
+
+    [
+        (o1.result,record(new,config,opening,first,second,&o1)),
+        (r2,record(new,config,opening,second,first,&o2)),
+    ]
+}
+
+// plays pairs of games across threads until all pairs are played or the sprt is decided, and appends the records of the games to the record file of the config
+pub fn run<P,A,S,F>(new: fn() -> S, config: &Config, progress: &mut F) -> Score
+where P: Player, A: Action, S: Notation<P,A>, F: FnMut(&Score) {
+    let threads = config.threads.unwrap_or_else(|| {
+        std::thread::available_parallelism().map_or(1,|n| n.get())
+    });
+    let next = AtomicUsize::new(0);
+    let stop = AtomicBool::new(false);
+    let (tx,rx) = mpsc::channel();
+    let mut score = Score::default();
+    let mut file = config.record.as_ref().map(|path| {
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap_or_else(|e| panic!("could not open {}: {}",path,e))
+    });
+
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            let tx = tx.clone();
+            let (next,stop) = (&next,&stop);
+            scope.spawn(move || {
+                while !stop.load(Ordering::Relaxed) {
+                    let i = next.fetch_add(1,Ordering::Relaxed);
+                    if i >= config.pairs {
+                        break;
+                    }
+                    let (_,actions) = opening(new,config.start,config.seed.wrapping_add(i as u64));
+                    if tx.send(pair(new,config,&actions)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+
+        for games in rx {
+            for (result,record) in games {
+                score.add(result);
+                if let Some(file) = file.as_mut() {
+                    record.write(file).expect("could not write record");
+                }
+            }
+            progress(&score);
+
+            if let Some(sprt) = config.sprt {
+                if sprt.verdict(&score).1 != Verdict::Continue {
+                    stop.store(true,Ordering::Relaxed);
+                }
+            }
+        }
+    });
+
+    score
+}
@@ -130,6 +130,10 @@ int main(int argc, char* argv[])
         {
             arbor_prob(game);
         }
+        else if (strcmp(arg, "ordinal") == 0)
+        {
+            // actions are always ordinal indices
+        }
         else if (strcmp(arg, "policy:rollout") == 0)
         {
             eval_policy = ARBOR_EVAL_ROLLOUT;
@@ -45,6 +45,7 @@ pub struct Connect4 {
     side: bool,
     winner: Disc,
     hash: u64,
+    mirror: u64,
 }
 
 const NEWGAME: Connect4 = 
@@ -54,6 +55,7 @@ const NEWGAME: Connect4 =
         side: true,
         winner: Disc::N,
         hash: 0,
+        mirror: 0,
     };
 
 impl Display for Connect4 {
@@ -91,6 +93,13 @@ impl Display for Connect4 {
     }
 }
 
+// equal game states have the same discs and side to play, whatever their hashes are
+impl PartialEq for Connect4 {
+    fn eq(&self, other: &Connect4) -> bool {
+        (self.space == other.space) && (self.side == other.side)
+    }
+}
+
 impl Connect4 {
     pub fn new() -> Self {
         NEWGAME
@@ -100,10 +109,8 @@ impl Connect4 {
     pub fn load(moves: &[Column]) -> Connect4 {
         let mut g = Self::new();
         for m in moves {
-            println!("{}",g);
             g = g.make(*m);
         }
-        println!("{}",g);
         g
     }
     
@@ -160,7 +167,11 @@ impl Connect4 {
 }
 
 impl Player for Disc {}
-impl Action for Column {}
+impl Action for Column {
+    fn key(&self) -> Option<u64> {
+        Some(*self as u64)
+    }
+}
 
 impl GameState<Disc,Column> for Connect4 {
     
@@ -186,6 +197,9 @@ impl GameState<Disc,Column> for Connect4 {
                 next.space[i] = color;
                 next.hash ^= if next.side {ZTABLE[i]} else {ZTABLE[i + W*H]};
                 next.hash ^= ZTURN;
+                let j = row*W + (W - 1 - column);
+                next.mirror ^= if next.side {ZTABLE[j]} else {ZTABLE[j + W*H]};
+                next.mirror ^= ZTURN;
                 break;
             }
             row += 1;
@@ -224,7 +238,263 @@ impl GameState<Disc,Column> for Connect4 {
         self.hash
     }
 
+    // the board can be reflected left to right
+    fn canonical(&self) -> (u64,u8) {
+        if self.mirror < self.hash {(self.mirror,1)} else {(self.hash,0)}
+    }
+
+    fn transform(&self, c: Column, symmetry: u8) -> Column {
+        if symmetry == 1 {COL[W - 1 - c as usize]} else {c}
+    }
+
+    fn inverse(&self, c: Column, symmetry: u8) -> Column {
+        self.transform(c,symmetry)
+    }
+
     fn player(&self) -> Disc {
         if self.side {Disc::R} else {Disc::Y}
     }
+    
+    // prefer moves that win immediately
+    fn rollout_weight(&self, c: Column) -> f32 {
+        let next = self.make(c);
+        if next.winner == Disc::N {0.0} else {1.0}
+    }
+}
+
+// columns are numbered 1 to 7 from the left
+impl Notation<Disc,Column> for Connect4 {
+    fn parse(&self, text: &str) -> Option<Column> {
+        let c = text.trim().parse::<usize>().ok()?;
+        if !(1..=W).contains(&c) || self.gameover().is_some() {
+            return None;
+        }
+
+        let mut legal = false;
+        self.actions(&mut |a| legal |= a == COL[c-1]);
+        legal.then_some(COL[c-1])
+    }
+
+    fn format(&self, action: Column) -> String {
+        (action as usize + 1).to_string()
+    }
+}
+
+// the discs of each column from the bottom as "R" or "Y", with columns separated by "/" from the left
+// e.g. "/RY//R///" and the side to play follows from the number of discs because R plays first
+impl Position<Disc,Column> for Connect4 {
+    fn position(&self) -> String {
+        let mut columns = Vec::new();
+        for c in 0..W {
+            let mut column = String::new();
+            for r in 0..H {
+                match self.space[r*W + c] {
+                    Disc::N => break,
+                    disc => column.push_str(&disc.to_string()),
+                }
+            }
+            columns.push(column);
+        }
+        columns.join("/")
+    }
+
+    fn from_position(text: &str) -> Result<Self,String> {
+        let columns: Vec<&str> = text.trim().split('/').collect();
+        if columns.len() != W {
+            return Err(format!("expected {} columns in {}",W,text));
+        }
+
+        let mut game = NEWGAME;
+        let mut discs = (0i32,0i32);
+        for (c,column) in columns.iter().enumerate() {
+            if column.chars().count() > H {
+                return Err(format!("expected at most {} discs in column {}",H,c + 1));
+            }
+            for (r,disc) in column.chars().enumerate() {
+                let i = r*W + c;
+                let j = r*W + (W - 1 - c);
+                match disc {
+                    'R' => {
+                        game.space[i] = Disc::R;
+                        game.hash ^= ZTABLE[i + W*H];
+                        game.mirror ^= ZTABLE[j + W*H];
+                        discs.0 += 1;
+                    },
+                    'Y' => {
+                        game.space[i] = Disc::Y;
+                        game.hash ^= ZTABLE[i];
+                        game.mirror ^= ZTABLE[j];
+                        discs.1 += 1;
+                    },
+                    _ => return Err(format!("expected R or Y but found {}",disc)),
+                }
+            }
+        }
+
+        game.side = match discs.0 - discs.1 {
+            0 => true,
+            1 => false,
+            _ => return Err(format!("expected R to play first but found {} R and {} Y",discs.0,discs.1)),
+        };
+        if !game.side {
+            game.hash ^= ZTURN;
+            game.mirror ^= ZTURN;
+        }
+
+        // only the side that played last can have four in a row
+        let mut winners = Vec::new();
+        for r in 0..H {
+            for c in 0..W {
+                let disc = game.space[r*W + c];
+                let four = [(1,0),(0,1),(1,1),(1,-1)].iter().any(|&(dr,dc)| {
+                    (1..4).all(|k| {
+                        let (r,c) = (r as i32 + k*dr,c as i32 + k*dc);
+                        (0..H as i32).contains(&r) && (0..W as i32).contains(&c) &&
+                            game.space[(r*W as i32 + c) as usize] == disc
+                    })
+                });
+                if four && (disc != Disc::N) && !winners.contains(&disc) {
+                    winners.push(disc);
+                }
+            }
+        }
+        let last = if game.side {Disc::Y} else {Disc::R};
+        match winners[..] {
+            [] => {},
+            [disc] if disc == last => game.winner = disc,
+            _ => return Err(format!("found four in a row for the side to play in {}",text)),
+        }
+
+        let mut full = true;
+        game.actions(&mut |_| full = false);
+        game.gameover = full || (game.winner != Disc::N);
+        Ok(game)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn connect4_rollout_weight_wins() {
+        let game = Connect4::load(&[C1,C2,C1,C2,C1,C2]);
+        assert!(game.rollout_weight(C1) == 1.0);
+        assert!(game.rollout_weight(C3) == 0.0);
+    }
+    
+    #[test]
+    fn connect4_greedy_rollouts_take_wins() {
+        let game = Connect4::load(&[C4,C1,C4,C1,C4,C2]);
+        let mut mcts = MCTS::new(game)
+            .with_rollout_policy(RolloutPolicy::EpsilonGreedy(0.1));
+        mcts.ponder(5000);
+        assert!(mcts.best() == Some(C4));
+    }
+
+    #[test]
+    fn connect4_symmetry() {
+        let left = Connect4::load(&[C1,C4,C2,C3]);
+        let right = Connect4::load(&[C7,C4,C6,C5]);
+        assert!(left.hash() != right.hash());
+        assert!(left.canonical().0 == right.canonical().0);
+        for &c in COL.iter() {
+            assert!(left.inverse(left.transform(c,1),1) == c);
+        }
+
+        let game = Connect4::load(&[C4,C1,C4,C1,C4,C2]);
+        let mut mcts = MCTS::new(game).with_symmetry();
+        mcts.ponder(5000);
+        assert!(mcts.best() == Some(C4));
+    }
+
+    #[test]
+    fn connect4_advance() {
+        // self play that keeps the tree between turns
+        let mut game = Connect4::new();
+        let mut mcts = MCTS::new(game).with_verified_transposition();
+        let mut kept = 0;
+        while game.gameover().is_none() {
+            mcts.ponder(1000);
+            let action = mcts.best().expect("should find a best action");
+            game = game.make(action);
+            if game.gameover().is_none() && mcts.advance(action) {
+                kept += 1;
+            }
+        }
+        assert!(kept > 0);
+    }
+
+    #[test]
+    fn connect4_notation() {
+        let game = Connect4::new();
+        assert!(game.legal() == ["1","2","3","4","5","6","7"]);
+        assert!(game.parse("4") == Some(C4));
+        assert!(game.parse("8").is_none());
+
+        // a full column is not legal
+        let game = Connect4::load(&[C1,C1,C1,C1,C1,C1]);
+        assert!(game.parse("1").is_none());
+        game.actions(&mut |a| assert!(game.parse(&game.format(a)) == Some(a)));
+    }
+
+    // the game state read from its position has the same hashes as the one that was played
+    fn round_trip(game: &Connect4) {
+        let copy = Connect4::from_position(&game.position()).expect("should read the position");
+        assert!(copy == *game);
+        assert!(copy.hash() == game.hash());
+        assert!(copy.canonical() == game.canonical());
+    }
+
+    #[test]
+    fn connect4_position() {
+        let game = Connect4::new();
+        assert!(game.position() == "//////");
+
+        let game = Connect4::load(&[C4,C1,C4,C1,C4,C2]);
+        assert!(game.position() == "YY/Y//RRR///");
+        assert!(Connect4::from_position("YY/Y//RRR///") == Ok(game));
+        round_trip(&game);
+
+        // every game state of a game including the win
+        let mut game = Connect4::new();
+        let mut i = 0;
+        while game.gameover().is_none() {
+            let mut actions = Vec::new();
+            game.actions(&mut |c| actions.push(c));
+            game = game.make(actions[i % actions.len()]);
+            round_trip(&game);
+            i += 3;
+        }
+
+        assert!(Connect4::from_position("").is_err());
+        assert!(Connect4::from_position("R/////").is_err());
+        assert!(Connect4::from_position("RR//////").is_err());
+        assert!(Connect4::from_position("RYRYRYR//////").is_err());
+        assert!(Connect4::from_position("X//////").is_err());
+        assert!(Connect4::from_position("RRRR/YYY/Y////").is_err());
+        assert!(Connect4::from_position("RRRR/YYY/////").unwrap().gameover() == Some(GameResult::Lose));
+    }
+
+    #[test]
+    fn connect4_perft() {
+        let game = Connect4::new();
+        let counts = [7,49,343,2401,16807,117649,823536];
+        for (d,&n) in counts.iter().enumerate() {
+            assert!(perft(&game,d as u32 + 1,false) == n);
+        }
+        assert!(perft(&game,7,true) == 823536);
+
+        // only the column that is filled first loses a leaf
+        let split = divide(&game,7,true);
+        assert!(split.iter().map(|&(_,n)| n).sum::<u64>() == 823536);
+        assert!(split.iter().all(|&(_,n)| n == 117648));
+    }
+
+    #[test]
+    fn connect4_conformance() {
+        if let Err(violation) = conformance(&Connect4::new(),500,1) {
+            panic!("{}",violation);
+        }
+    }
 }
\ No newline at end of file
@@ -34,7 +34,7 @@ pub const PIT: [Pit; NP] = [
 
 mod zobrist;
 
-#[derive(Copy,Clone,Debug)]
+#[derive(Copy,Clone,Debug,PartialEq)]
 pub struct Mancala {
     pub pit: [u8; NP],
     side: Player
@@ -95,7 +95,6 @@ impl Mancala {
     }
     
 
-    #[allow(dead_code)]
     fn heuristic(&self) -> f32 {
         let (fb,eb) = match self.side {
             Player::L => (LB,RB),
@@ -113,7 +112,7 @@ impl Mancala {
                 0.0
             }
         } else {
-            let d = (fs - es) as f32;
+            let d = (fs as f32) - (es as f32);
             let n = NS as f32;
             0.5*(1.0 + d/n)
         }
@@ -136,10 +135,8 @@ impl Mancala {
     pub fn load(moves: &[Pit]) -> Mancala {
         let mut g = Self::new();
         for m in moves {
-            println!("{}",g);
             g = g.make(*m);
         }
-        println!("{}",g);
         g
     }
 
@@ -149,7 +146,11 @@ impl Mancala {
     
 }
 
-impl Action for Pit {}
+impl Action for Pit {
+    fn key(&self) -> Option<u64> {
+        Some(*self as u64)
+    }
+}
 impl arbor::Player for Player {}
 
 impl GameState<Player,Pit> for Mancala {
@@ -290,6 +291,83 @@ impl GameState<Player,Pit> for Mancala {
     fn player(&self) -> Player {
         self.side
     }
+    
+    fn custom_evaluation(&self) -> f32 {
+        self.heuristic()
+    }
+    
+    // difference in stores normalized by the number of stones
+    fn score(&self) -> f32 {
+        let (fb,eb) = match self.side {
+            Player::L => (LB,RB),
+            Player::R => (RB,LB),
+        };
+        ((self.pit[fb] as f32) - (self.pit[eb] as f32))/(NS as f32)
+    }
+}
+
+// pits are numbered 1 to 6 from the side of the player to move
+impl Notation<Player,Pit> for Mancala {
+    fn parse(&self, text: &str) -> Option<Pit> {
+        let p = text.trim().parse::<usize>().ok()?;
+        if !(1..=6).contains(&p) || self.gameover().is_some() {
+            return None;
+        }
+
+        let first = match self.side {
+            Player::L => L1 as usize,
+            Player::R => R1 as usize,
+        };
+        let pit = first + p - 1;
+        (self.pit[pit] > 0).then_some(PIT[pit])
+    }
+
+    fn format(&self, action: Pit) -> String {
+        let p = action as usize;
+        let first = if p < RB {R1 as usize} else {L1 as usize};
+        (p - first + 1).to_string()
+    }
+}
+
+// the stones in each pit from R1 to the right bank and then from L1 to the left bank, then the side to play
+// e.g. "4,4,4,4,4,4,0,4,4,4,4,4,4,0 R"
+impl Position<Player,Pit> for Mancala {
+    fn position(&self) -> String {
+        let pits: Vec<String> = self.pit.iter().map(|n| n.to_string()).collect();
+        let side = match self.side {
+            Player::L => "L",
+            Player::R => "R",
+        };
+        format!("{} {}",pits.join(","),side)
+    }
+
+    fn from_position(text: &str) -> Result<Self,String> {
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        let (pits,side) = match fields[..] {
+            [pits,side] => (pits,side),
+            _ => return Err(format!("expected pits and a side in {}",text)),
+        };
+        let side = match side {
+            "L" => Player::L,
+            "R" => Player::R,
+            _ => return Err(format!("expected side L or R but found {}",side)),
+        };
+
+        let pits: Vec<&str> = pits.split(',').collect();
+        if pits.len() != NP {
+            return Err(format!("expected {} pits but found {}",NP,pits.len()));
+        }
+        let mut pit = [0;NP];
+        for (p,n) in pits.iter().enumerate() {
+            pit[p] = n.parse::<u8>().map_err(|_| format!("expected a number of stones but found {}",n))?;
+        }
+
+        let total: usize = pit.iter().map(|&n| n as usize).sum();
+        if total != NS {
+            return Err(format!("expected {} stones but found {}",NS,total));
+        }
+        Ok(Mancala {pit, side})
+    }
 }
 
 #[cfg(test)]
@@ -304,6 +382,55 @@ mod test {
         mcts.best().expect("Should find a best action")
     }
 
+    #[test]
+    fn mancala_notation() {
+        let game = Mancala::new();
+        assert!(game.legal() == ["1","2","3","4","5","6"]);
+
+        // pits are numbered from the left of the side to play
+        let game = game.make(Pit::R1);
+        game.actions(&mut |a| assert!(game.parse(&game.format(a)) == Some(a)));
+        assert!(game.parse("7").is_none());
+    }
+
+    // the game state read from its position has the same hashes as the one that was played
+    fn round_trip(game: &Mancala) {
+        let copy = Mancala::from_position(&game.position()).expect("should read the position");
+        assert!(copy == *game);
+        assert!(copy.hash() == game.hash());
+        assert!(copy.canonical() == game.canonical());
+    }
+
+    #[test]
+    fn mancala_position() {
+        let game = Mancala::new();
+        assert!(game.position() == "4,4,4,4,4,4,0,4,4,4,4,4,4,0 R");
+
+        let mut game = Mancala::load(&[Pit::R3,Pit::R6]);
+        assert!(game.position() == "4,4,0,5,5,0,2,5,5,5,5,4,4,0 L");
+        while game.gameover().is_none() {
+            round_trip(&game);
+            let mut actions = Vec::new();
+            game.actions(&mut |p| actions.push(p));
+            game = game.make(actions[0]);
+        }
+        round_trip(&game);
+
+        assert!(Mancala::from_position("").is_err());
+        assert!(Mancala::from_position("4,4,4,4,4,4,0,4,4,4,4,4,4,0").is_err());
+        assert!(Mancala::from_position("4,4,4,4,4,4,0,4,4,4,4,4,4,0 X").is_err());
+        assert!(Mancala::from_position("4,4,4,4,4,4,0,4,4,4,4,4,4 R").is_err());
+        assert!(Mancala::from_position("4,4,4,4,4,4,0,4,4,4,4,4,4,1 R").is_err());
+        assert!(Mancala::from_position("4,4,4,4,4,4,0,4,4,4,4,4,a,0 R").is_err());
+    }
+
+    #[test]
+    fn mancala_conformance() {
+        if let Err(violation) = conformance(&Mancala::new(),500,1) {
+            panic!("{}",violation);
+        }
+    }
+
     #[test]
     fn mancala_free_move_1() {
         let mut game = Mancala::new();
@@ -403,6 +530,23 @@ mod test {
         assert!(game.pit[Pit::L3 as usize] == 0);
     }
 
+    #[test]
+    fn mancala_heuristic_behind() {
+        let game = Mancala::load(&[R6,L6,R1]);
+        assert!(game.custom_evaluation() < 0.5);
+    }
+
+    #[test]
+    fn mancala_best_move_cutoff() {
+        let game = Mancala::load(&[R6,L6,R2]);
+        let mut mcts = MCTS::new(game)
+            .with_transposition()
+            .with_rollout_depth(8)
+            .with_evaluation_blend(0.5);
+        mcts.ponder(10000);
+        assert!(mcts.best() == Some(R6));
+    }
+
     #[test]
     fn mancala_best_move_split() {
         let m = best(&[R6,L6]);
@@ -415,4 +559,29 @@ mod test {
         assert!(m == R6);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn mancala_score() {
+        let mut game = Mancala::new();
+        while game.gameover().is_none() {
+            let mut last = None;
+            game.actions(&mut |a| last = Some(a));
+            game = game.make(last.expect("should have an action"));
+        }
+        
+        let score = game.score();
+        match game.gameover() {
+            Some(GameResult::Win) => assert!(score > 0.0),
+            Some(GameResult::Lose) => assert!(score < 0.0),
+            _ => assert!(score == 0.0),
+        }
+        
+        let game = Mancala::load(&[R6,L6]);
+        let mut mcts = MCTS::new(game).with_score_weight(0.5);
+        mcts.ponder(2000);
+        mcts.ply(&mut |(_a,w,_s,m)| {
+            assert!((-1.0..=1.0).contains(&m));
+            assert!((0.0..=1.0).contains(&w));
+        });
+    }
+
+}
@@ -14,7 +14,8 @@ class Game:
         if player is None:
             player = BACKEND
         
-        opt = []
+        # actions are exchanged as ordinal indices
+        opt = ["ordinal"]
 
         opt += [f"policy:{player.policy}"]
 
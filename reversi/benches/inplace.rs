@@ -0,0 +1,57 @@
+use arbor::*;
+use reversi::*;
+use std::fmt;
+use std::time::Instant;
+
+// Reversi without the in-place apply method, so the search falls back to make
+#[derive(Debug,Clone,Copy)]
+struct Copied(Reversi);
+
+impl fmt::Display for Copied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl GameState<Disc,Move> for Copied {
+    fn actions<F>(&self,f: &mut F) where F: FnMut(Move) {
+        self.0.actions(f)
+    }
+
+    fn make(&self,m: Move) -> Self {
+        Copied(self.0.make(m))
+    }
+
+    fn gameover(&self) -> Option<GameResult> {
+        self.0.gameover()
+    }
+
+    fn player(&self) -> Disc {
+        self.0.player()
+    }
+
+    fn hash(&self) -> u64 {
+        self.0.hash()
+    }
+}
+
+fn bench<S: GameState<Disc,Move>>(name: &str, game: S, iterations: usize) {
+    let mut mcts = MCTS::new(game);
+    let start = Instant::now();
+    mcts.ponder(iterations);
+    let seconds = start.elapsed().as_secs_f32();
+    println!("{:<8} {:>8} iterations {:>8.3} s {:>10.0} iterations/s",name,iterations,seconds,(iterations as f32)/seconds);
+}
+
+fn main() {
+    let iterations = 100000;
+    let game = Reversi::new();
+    
+    // warm up
+    bench("warmup",game,iterations/10);
+    
+    for _ in 0..3 {
+        bench("copy",Copied(game),iterations);
+        bench("in-place",game,iterations);
+    }
+}
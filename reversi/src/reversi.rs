@@ -34,7 +34,7 @@ impl Display for Disc {
 #[derive(Debug,Copy,Clone,PartialEq)]
 pub enum Move {Pass,Capture(u64)}
 
-#[derive(Debug,Clone,Copy)]
+#[derive(Debug,Clone,Copy,PartialEq)]
 pub struct Reversi {
     pub f: u64,
     pub e: u64,
@@ -52,6 +52,8 @@ trait BitBoard {
 }
 
 const FULLBOARD: u64 = 0xFFFFFFFFFFFFFFFFu64;
+const CORNERS: u64 = 0x8100000000000081u64;
+const XSQUARES: u64 = 0x0042000000004200u64;
 const EASTBOUND: u64 = 0x8080808080808080u64;
 const WESTBOUND: u64 = 0x0101010101010101u64;
 
@@ -80,6 +82,55 @@ fn southeast(x: u64) -> u64 {(x >> 7) & !WESTBOUND}
 #[inline]
 fn southwest(x: u64) -> u64 {(x >> 9) & !EASTBOUND}
 
+// reflect columns
+#[inline]
+fn mirror(x: u64) -> u64 {x.reverse_bits().swap_bytes()}
+
+// reflect rows
+#[inline]
+fn flip(x: u64) -> u64 {x.swap_bytes()}
+
+// swap rows and columns
+#[inline]
+fn transpose(mut x: u64) -> u64 {
+    let mut t;
+    t = 0x0F0F0F0F00000000 & (x ^ (x << 28));
+    x ^= t ^ (t >> 28);
+    t = 0x3333000033330000 & (x ^ (x << 14));
+    x ^= t ^ (t >> 14);
+    t = 0x5500550055005500 & (x ^ (x << 7));
+    x ^= t ^ (t >> 7);
+    x
+}
+
+// each bit of the symmetry index selects one of the reflections
+#[inline]
+fn symmetry(mut x: u64, t: u8) -> u64 {
+    if t & 4 != 0 {x = transpose(x);}
+    if t & 2 != 0 {x = flip(x);}
+    if t & 1 != 0 {x = mirror(x);}
+    x
+}
+
+#[inline]
+fn unsymmetry(mut x: u64, t: u8) -> u64 {
+    if t & 1 != 0 {x = mirror(x);}
+    if t & 2 != 0 {x = flip(x);}
+    if t & 4 != 0 {x = transpose(x);}
+    x
+}
+
+#[inline]
+fn scramble(mut f: u64, mut e: u64, pass: bool) -> u64 {
+    let mut result = mask(pass) & 0x9E3779B97F4A7C15;
+    for _ in 0..10 {
+        f = f.rotate_right(23);
+        e = e.rotate_right(37);
+        result ^= f ^ e;
+    }
+    result
+}
+
 
 pub struct IterBB {
     bits: u64,
@@ -137,28 +188,28 @@ impl Display for Reversi {
 /*
             White Turn
   ---------------------------------
+8 | - | - | - | - | - | - | - | - |
+  ---------------------------------
 7 | - | - | - | - | - | - | - | - |
   ---------------------------------
 6 | - | - | - | - | - | - | - | - |
   ---------------------------------
-5 | - | - | - | - | - | - | - | - |
+5 | - | - | - | W | B | - | - | - |
   ---------------------------------
-4 | - | - | - | W | B | - | - | - |
+4 | - | - | - | B | W | - | - | - |
   ---------------------------------
-3 | - | - | - | B | W | - | - | - |
+3 | - | - | - | - | - | - | - | - |
   ---------------------------------
 2 | - | - | - | - | - | - | - | - |
   ---------------------------------
 1 | - | - | - | - | - | - | - | - |
   ---------------------------------
-0 | - | - | - | - | - | - | - | - |
-  ---------------------------------
-    0   1   2   3   4   5   6   7
+    a   b   c   d   e   f   g   h
 */
 
 
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let colnum = "    0   1   2   3   4   5   6   7\n";
+        let colnum = "    a   b   c   d   e   f   g   h\n";
         let rowsep = "  ---------------------------------\n";
 
         let moves = self.parallel_capture();
@@ -175,7 +226,7 @@ impl Display for Reversi {
         let (white,black) = if self.side == Disc::W {(self.f,self.e)} else {(self.e,self.f)};
 
         for h in 0..S {
-            result.push_str(&format!("{} ",7-h));
+            result.push_str(&format!("{} ",8-h));
             for w in 0..S {
                 let space = u64::space(7 - h, w);
                 let piece = 
@@ -215,13 +266,48 @@ impl Reversi {
     pub fn load(moves: &[Move]) -> Reversi {
         let mut g = Self::new();
         for m in moves {
-            // println!("{}",g);
             g = g.make(*m);
         }
-        println!("{}",g);
         g
     }
 
+    // discs captured by placing a disc on space i
+    fn captures(&self, i: u64) -> u64 {
+        // 0 1 2 3 4 5 6 7
+        // W B B B B B B W
+        // 1 1 0 0 0 0 0 0
+        // 1 1 1 0 0 0 0 0
+        // 1 1 1 1 0 0 0 0
+        // 1 1 1 1 1 0 0 0
+        // 1 1 1 1 1 1 0 0
+        // 1 1 1 1 1 1 1 0
+        #[inline]
+        fn capture<F>(mut p: u64, f: u64, e: u64, shift: F) -> u64 where F: Fn(u64) -> u64 {
+            p |= shift(p) & e;
+            p |= shift(p) & e;
+            p |= shift(p) & e;
+            p |= shift(p) & e;
+            p |= shift(p) & e;
+            p |= shift(p) & e;
+        
+            mask((shift(p) & f) != 0) & p
+        }
+
+        let e = self.e;
+        let f = self.f;
+        let p = 1 << i;
+        
+        0 
+            | capture(p,f,e,north)
+            | capture(p,f,e,south)
+            | capture(p,f,e,east)
+            | capture(p,f,e,west)
+            | capture(p,f,e,northeast)
+            | capture(p,f,e,northwest)
+            | capture(p,f,e,southeast)
+            | capture(p,f,e,southwest)
+    }
+
     // 0 1 2 3 4 5 6 7
     // - - W B B - - -
     // 0 0 0 1 0 0 0 0
@@ -276,7 +362,14 @@ impl Reversi {
 }
 
 
-impl Action for Move {}
+impl Action for Move {
+    fn key(&self) -> Option<u64> {
+        match *self {
+            Move::Pass => Some(64),
+            Move::Capture(i) => Some(i),
+        }
+    }
+}
 impl Player for Disc {}
 
 impl GameState<Disc,Move> for Reversi {
@@ -327,39 +420,7 @@ impl GameState<Disc,Move> for Reversi {
                 }
             },
             Move::Capture(i) => {
-                // 0 1 2 3 4 5 6 7
-                // W B B B B B B W
-                // 1 1 0 0 0 0 0 0
-                // 1 1 1 0 0 0 0 0
-                // 1 1 1 1 0 0 0 0
-                // 1 1 1 1 1 0 0 0
-                // 1 1 1 1 1 1 0 0
-                // 1 1 1 1 1 1 1 0
-                #[inline]
-                fn capture<F>(mut p: u64, f: u64, e: u64, shift: F) -> u64 where F: Fn(u64) -> u64 {
-                    p |= shift(p) & e;
-                    p |= shift(p) & e;
-                    p |= shift(p) & e;
-                    p |= shift(p) & e;
-                    p |= shift(p) & e;
-                    p |= shift(p) & e;
-                    
-                    mask((shift(p) & f) != 0) & p
-                }
-                
-                let e = self.e;
-                let f = self.f;
-                let p = 1 << i;
-                
-                let c = 0 
-                    | capture(p,f,e,north)
-                    | capture(p,f,e,south)
-                    | capture(p,f,e,east)
-                    | capture(p,f,e,west)
-                    | capture(p,f,e,northeast)
-                    | capture(p,f,e,northwest)
-                    | capture(p,f,e,southeast)
-                    | capture(p,f,e,southwest);
+                let c = self.captures(i);
                 
                 Reversi {
                     f: self.e & !c,
@@ -371,19 +432,331 @@ impl GameState<Disc,Move> for Reversi {
         }        
     }
     
+    fn apply(&mut self,m: Move) {
+        match m {
+            Move::Pass => {
+                std::mem::swap(&mut self.f,&mut self.e);
+                self.pass = true;
+            },
+            Move::Capture(i) => {
+                let c = self.captures(i);
+                let f = self.f | c;
+                self.f = self.e & !c;
+                self.e = f;
+                self.pass = false;
+            }
+        }
+        self.side = self.side.other();
+    }
+    
     fn hash(&self) -> u64 {
-        let mut f = self.f;
-        let mut e = self.e;
-        let mut result = 0;
-        for _ in 0..10 {
-            f = f.rotate_right(23);
-            e = e.rotate_right(37);
-            result ^= f ^ e;
+        scramble(self.f,self.e,self.pass)
+    }
+    
+    // the board has the 8 symmetries of a square
+    fn canonical(&self) -> (u64,u8) {
+        let mut best = (self.hash(),0);
+        for t in 1..8 {
+            let hash = scramble(symmetry(self.f,t),symmetry(self.e,t),self.pass);
+            if hash < best.0 {
+                best = (hash,t);
+            }
+        }
+        best
+    }
+    
+    fn transform(&self, m: Move, t: u8) -> Move {
+        match m {
+            Move::Pass => Move::Pass,
+            Move::Capture(i) => Move::Capture(symmetry(1 << i,t).trailing_zeros() as u64),
+        }
+    }
+    
+    fn inverse(&self, m: Move, t: u8) -> Move {
+        match m {
+            Move::Pass => Move::Pass,
+            Move::Capture(i) => Move::Capture(unsymmetry(1 << i,t).trailing_zeros() as u64),
         }
-        result
     }
     
     fn player(&self) -> Disc {
         self.side
     }
+    
+    // difference in discs normalized by the number of spaces
+    fn score(&self) -> f32 {
+        ((self.f.count_ones() as f32) - (self.e.count_ones() as f32))/64.0
+    }
+    
+    // prefer corners and avoid the squares diagonally adjacent to them
+    fn rollout_weight(&self, m: Move) -> f32 {
+        match m {
+            Move::Pass => 0.0,
+            Move::Capture(i) => {
+                let p = 1 << i;
+                if CORNERS.has(p) {
+                    2.0
+                } else if XSQUARES.has(p) {
+                    -2.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+    
+    fn rollout_action<R>(&self, rand: &mut R) -> Option<Move> where R: FnMut() -> u64 {
+        let moves = self.parallel_capture();
+        let max = moves.count_ones() as u64;
+        if max == 0 {
+            return Some(Move::Pass);
+        }
+        
+        let mask = max.next_power_of_two() - 1;
+        let mut r = rand() & mask;
+        while r >= max {
+            r = rand() & mask;
+        }
+        
+        moves.iter().nth(r as usize).map(Move::Capture)
+    }
+}
+
+// spaces are written as a column letter and a row number (e.g. "d3"), or "pass"
+impl Notation<Disc,Move> for Reversi {
+    fn parse(&self, text: &str) -> Option<Move> {
+        let text = text.trim();
+        if self.gameover().is_some() {
+            return None;
+        }
+
+        let action = if text == "pass" {
+            Move::Pass
+        } else {
+            let mut chars = text.chars();
+            let col = chars.next()?;
+            let row = chars.next()?;
+            if chars.next().is_some() || !('a'..='h').contains(&col) || !('1'..='8').contains(&row) {
+                return None;
+            }
+            Move::Capture(8*(row as u64 - '1' as u64) + (col as u64 - 'a' as u64))
+        };
+
+        let mut legal = false;
+        self.actions(&mut |a| legal |= a == action);
+        legal.then_some(action)
+    }
+
+    fn format(&self, action: Move) -> String {
+        match action {
+            Move::Pass => String::from("pass"),
+            Move::Capture(u) => {
+                let col = (b'a' + (u % 8) as u8) as char;
+                let row = (b'1' + (u / 8) as u8) as char;
+                format!("{}{}",col,row)
+            },
+        }
+    }
+}
+
+// the spaces from a1 to h8 row by row as "-", "W", or "B", then the side to play, then "pass" if the last action was a pass
+// e.g. "---------------------------BW------WB--------------------------- W"
+impl Position<Disc,Move> for Reversi {
+    fn position(&self) -> String {
+        let (white,black) = if self.side == Disc::W {(self.f,self.e)} else {(self.e,self.f)};
+        let mut result = String::new();
+        for i in 0..64 {
+            let p = 1 << i;
+            result.push(if white.has(p) {'W'} else if black.has(p) {'B'} else {'-'});
+        }
+        result.push_str(&format!(" {}",self.side));
+        if self.pass {
+            result.push_str(" pass");
+        }
+        result
+    }
+
+    fn from_position(text: &str) -> Result<Self,String> {
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        let (board,side,pass) = match fields[..] {
+            [board,side] => (board,side,false),
+            [board,side,"pass"] => (board,side,true),
+            _ => return Err(format!("expected a board, a side, and an optional pass in {}",text)),
+        };
+        if board.chars().count() != 64 {
+            return Err(format!("expected 64 spaces in {}",board));
+        }
+        let side = match side {
+            "W" => Disc::W,
+            "B" => Disc::B,
+            _ => return Err(format!("expected side W or B but found {}",side)),
+        };
+
+        let mut white = 0;
+        let mut black = 0;
+        for (i,c) in board.chars().enumerate() {
+            match c {
+                'W' => white |= 1 << i,
+                'B' => black |= 1 << i,
+                '-' => {},
+                _ => return Err(format!("expected -, W, or B but found {}",c)),
+            }
+        }
+
+        let (f,e) = if side == Disc::W {(white,black)} else {(black,white)};
+        Ok(Reversi {f, e, side, pass})
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reversi_rollout_action_is_legal() {
+        let mut game = Reversi::new();
+        let mut seed = 1u64;
+        let mut rand = || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            seed
+        };
+        
+        while game.gameover().is_none() {
+            let m = game.rollout_action(&mut rand).expect("should choose an action");
+            let mut legal = false;
+            game.actions(&mut |a| legal |= a == m);
+            assert!(legal,"{:?} is not legal\n{}",m,game);
+            game = game.make(m);
+        }
+    }
+    
+    #[test]
+    fn reversi_rollout_policies() {
+        let game = Reversi::new();
+        for policy in [
+            RolloutPolicy::Custom,
+            RolloutPolicy::EpsilonGreedy(0.1),
+            RolloutPolicy::Softmax(1.0),
+            RolloutPolicy::Mast(0.1)
+        ] {
+            let mut mcts = MCTS::new(game).with_rollout_policy(policy);
+            mcts.ponder(1000);
+            assert!(mcts.best().is_some());
+        }
+    }
+    
+    #[test]
+    fn reversi_symmetry() {
+        let game = Reversi::new();
+        let mut openings = Vec::new();
+        game.actions(&mut |m| openings.push(game.make(m).canonical().0));
+        assert!(openings.len() == 4);
+        assert!(openings.iter().all(|&h| h == openings[0]));
+        
+        let game = Reversi::load(&[Move::Capture(0o23),Move::Capture(0o22)]);
+        for t in 0..8 {
+            assert!(game.transform(Move::Pass,t) == Move::Pass);
+            for i in 0..64 {
+                let m = Move::Capture(i);
+                assert!(game.inverse(game.transform(m,t),t) == m);
+            }
+            
+            // the transformed game state has the transformed actions
+            let f = symmetry(game.f,t);
+            let e = symmetry(game.e,t);
+            let other = Reversi {f, e, side: game.side, pass: game.pass};
+            let mut actions = Vec::new();
+            game.actions(&mut |m| actions.push(game.transform(m,t)));
+            let mut count = 0;
+            other.actions(&mut |m| {
+                assert!(actions.contains(&m));
+                count += 1;
+            });
+            assert!(count == actions.len());
+        }
+        
+        let mut mcts = MCTS::new(game).with_symmetry();
+        mcts.ponder(1000);
+        assert!(mcts.best().is_some());
+    }
+    
+    #[test]
+    fn reversi_score() {
+        let mut game = Reversi::new();
+        while game.gameover().is_none() {
+            let mut first = None;
+            game.actions(&mut |a| if first.is_none() {first = Some(a)});
+            game = game.make(first.expect("should have an action"));
+        }
+        
+        let score = game.score();
+        match game.gameover() {
+            Some(GameResult::Win) => assert!(score > 0.0),
+            Some(GameResult::Lose) => assert!(score < 0.0),
+            _ => assert!(score == 0.0),
+        }
+    }
+
+    #[test]
+    fn reversi_notation() {
+        let game = Reversi::new();
+        assert!(game.legal() == ["d3","c4","f5","e6"]);
+        assert!(game.parse("d3") == Some(Move::Capture(0o23)));
+        assert!(game.parse("a1").is_none());
+        assert!(game.parse("i9").is_none());
+        assert!(game.parse("pass").is_none());
+        assert!(game.format(Move::Capture(0o77)) == "h8");
+
+        let game = game.make(Move::Capture(0o23));
+        game.actions(&mut |a| assert!(game.parse(&game.format(a)) == Some(a)));
+    }
+
+    #[test]
+    fn reversi_position() {
+        let game = Reversi::new();
+        let text = "---------------------------BW------WB--------------------------- W";
+        assert!(game.position() == text);
+
+        let mut game = Reversi::load(&[Move::Capture(0o23),Move::Capture(0o22)]);
+        while game.gameover().is_none() {
+            let copy = Reversi::from_position(&game.position()).expect("should read the position");
+            assert!(copy.f == game.f && copy.e == game.e && copy.side == game.side && copy.pass == game.pass);
+            assert!(copy.position() == game.position());
+            assert!(copy.hash() == game.hash());
+            assert!(copy.canonical() == game.canonical());
+            let mut actions = Vec::new();
+            game.actions(&mut |m| actions.push(m));
+            game = game.make(actions[actions.len()/2]);
+        }
+
+        assert!(Reversi::from_position("").is_err());
+        assert!(Reversi::from_position(&text[..63]).is_err());
+        assert!(Reversi::from_position(&text.replace('W',"X")).is_err());
+        assert!(Reversi::from_position(&text.replace(" W"," R")).is_err());
+        assert!(Reversi::from_position(&format!("{} now",text)).is_err());
+        assert!(Reversi::from_position(&format!("{} pass",text)).unwrap().pass);
+    }
+
+    #[test]
+    fn reversi_perft() {
+        let game = Reversi::new();
+        let counts = [4,12,56,244,1396,8200,55092,390216];
+        for (d,&n) in counts.iter().enumerate() {
+            assert!(perft(&game,d as u32 + 1,false) == n);
+        }
+        assert!(perft(&game,8,true) == 390216);
+
+        let split = divide(&game,6,true);
+        assert!(split.len() == 4);
+        assert!(split.iter().all(|&(_,n)| n == 8200/4));
+    }
+
+    #[test]
+    fn reversi_conformance() {
+        if let Err(violation) = conformance(&Reversi::new(),200,1) {
+            panic!("{}",violation);
+        }
+    }
 }
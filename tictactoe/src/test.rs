@@ -1,6 +1,6 @@
 use super::tictactoe::Grid::*;
 use super::tictactoe::*;
-use arbor::MCTS;
+use arbor::*;
 
 fn best(moves: &[Grid]) -> Grid {
     let game = TicTacToe::load(&moves);
@@ -28,4 +28,701 @@ fn tictactoe_best_even2() {
 fn tictactoe_best_split() {
     let m = best(&[MM,TM,MR,ML]);
     assert!((m == BR) || (m == TR));
-}
\ No newline at end of file
+}
+
+fn best_batch(moves: &[Grid]) -> Grid {
+    let game = TicTacToe::load(moves);
+    let mut mcts = MCTS::new(game).with_transposition().with_batch_size(16);
+    mcts.ponder_batch(10000,&mut |states,values| {
+        assert!(states.len() == values.len());
+        values.iter_mut().for_each(|v| *v = 0.5);
+    });
+    mcts.best().expect("Should find a best action")
+}
+
+#[test]
+fn tictactoe_batch_obvious() {
+    assert!(best_batch(&[MM,TM,MR,ML,BR,TR]) == TL);
+}
+
+#[test]
+fn tictactoe_batch_even() {
+    assert!(best_batch(&[TL,MM,ML]) == BL);
+}
+
+#[test]
+fn tictactoe_decisive_rollouts() {
+    let game = TicTacToe::load(&[MM,ML,MR,TL]);
+    let mut mcts = MCTS::new(game)
+        .with_transposition()
+        .with_decisive_moves(DecisiveMoves::AntiDecisive);
+    mcts.ponder(2000);
+    assert!(mcts.best() == Some(BL));
+    assert!(mcts.info.lookahead > mcts.info.steps);
+}
+
+#[test]
+fn tictactoe_mast_rollouts() {
+    let game = TicTacToe::load(&[TL,MM,ML]);
+    let mut mcts = MCTS::new(game)
+        .with_transposition()
+        .with_rollout_policy(RolloutPolicy::Mast(0.1));
+    mcts.ponder(10000);
+    assert!(mcts.best() == Some(BL));
+}
+
+#[test]
+fn tictactoe_first_play_urgency() {
+    let game = TicTacToe::load(&[MM,TM,MR,ML,BR,TR]);
+    let mut mcts = MCTS::new(game)
+        .with_transposition()
+        .with_first_play_urgency(FirstPlayUrgency::Reduction(0.2));
+    mcts.ponder(1000);
+    assert!(mcts.best() == Some(TL));
+
+    let game = TicTacToe::new();
+    let mut mcts = MCTS::new(game)
+        .with_first_play_urgency(FirstPlayUrgency::Value(0.25));
+    mcts.ponder(1);
+    let mut unknown = 0;
+    let mut total = 0;
+    mcts.ply(&mut |(_a,w,_s,_m)| {
+        unknown += (w == 0.25) as u32;
+        total += 1;
+    });
+    assert!(unknown == 8);
+    assert!(total == 9);
+}
+
+#[test]
+fn tictactoe_symmetry() {
+    let corners = [TL,TR,BL,BR].map(|m| TicTacToe::load(&[m]).canonical().0);
+    assert!(corners.iter().all(|&h| h == corners[0]));
+
+    let game = TicTacToe::load(&[TL,MM,BR]);
+    for t in 0..8 {
+        for &m in ALLMOVES.iter() {
+            assert!(game.inverse(game.transform(m,t),t) == m);
+        }
+    }
+
+    let game = TicTacToe::new();
+    let mut mcts = MCTS::new(game).with_symmetry();
+    mcts.ponder(1000);
+    let mut count = 0;
+    mcts.ply(&mut |_| count += 1);
+    assert!(count == 9);
+
+    //There are only 765 essentially different positions
+    mcts.ponder(20000);
+    assert!(mcts.info.branch + mcts.info.terminal <= 765);
+}
+
+fn best_symmetry(moves: &[Grid]) -> Grid {
+    let game = TicTacToe::load(moves);
+    let mut mcts = MCTS::new(game).with_symmetry();
+    mcts.ponder(10000);
+    mcts.best().expect("Should find a best action")
+}
+
+#[test]
+fn tictactoe_symmetry_best() {
+    assert!(best_symmetry(&[MM,TM,MR,ML,BR,TR]) == TL);
+    assert!(best_symmetry(&[TL,MM,ML]) == BL);
+    assert!(best_symmetry(&[MM,ML,MR,TL]) == BL);
+    let m = best_symmetry(&[MM,TM,MR,ML]);
+    assert!((m == BR) || (m == TR));
+}
+
+#[test]
+fn tictactoe_contempt() {
+    // X to play the last space for a draw
+    let game = TicTacToe::load(&[TL,TM,TR,MM,ML,MR,BM,BL]);
+    let mut mcts = MCTS::new(game).with_contempt(0.2);
+    mcts.ponder(100);
+    mcts.ply(&mut |(_a,w,_s,_m)| assert!((w - 0.2).abs() < 1e-3));
+
+    // O can draw with BL or lose with BR, so the draw is valued from the perspective of O
+    let game = TicTacToe::load(&[TL,TM,TR,MM,ML,MR,BM]);
+    let mut mcts = MCTS::new(game).with_contempt(0.2);
+    mcts.ponder(1000);
+    assert!(mcts.best() == Some(BL));
+    mcts.ply(&mut |(a,w,_s,_m)| {
+        if a == BL {
+            assert!((w - 0.2).abs() < 1e-3);
+        } else {
+            assert!(w < 0.01);
+        }
+    });
+
+    // the same values come from random playouts when the leaves are not expanded
+    let mut mcts = MCTS::new(game).with_contempt(0.2).with_expansion_minimum(1000);
+    mcts.ponder(1000);
+    mcts.ply(&mut |(a,w,_s,_m)| {
+        if a == BL {
+            assert!((w - 0.2).abs() < 1e-3);
+        } else {
+            assert!(w < 0.01);
+        }
+    });
+}
+
+#[test]
+fn tictactoe_sample() {
+    // X wins with BR
+    let game = TicTacToe::load(&[TL,TM,MM,TR]);
+    let mut mcts = MCTS::new(game);
+    mcts.ponder(10000);
+    assert!(mcts.sample(0.0) == mcts.best());
+    
+    let wins = (0..100).filter(|_| mcts.sample(0.5) == Some(BR)).count();
+    assert!(wins > 90);
+    
+    let game = TicTacToe::new();
+    let mut mcts = MCTS::new(game);
+    mcts.ponder(1000);
+    let mut moves = Vec::new();
+    for _ in 0..100 {
+        let m = mcts.sample(4.0).expect("should sample a move");
+        if !moves.contains(&m) {
+            moves.push(m);
+        }
+    }
+    assert!(moves.len() > 2);
+}
+
+#[test]
+fn tictactoe_strength() {
+    assert!(Strength::level(Strength::MAX_LEVEL).temperature == 0.0);
+    assert!(Strength::level(0) == Strength::level(1));
+    assert!(Strength::level(1).temperature > Strength::level(2).temperature);
+    assert!(Strength::level(1).iterations < Strength::level(2).iterations);
+    
+    let game = TicTacToe::load(&[TL,TM,MM,TR]);
+    let mut mcts = MCTS::new(game);
+    assert!(mcts.play_like(Strength::level(Strength::MAX_LEVEL)) == Some(BR));
+    assert!(mcts.info.n as usize >= Strength::level(Strength::MAX_LEVEL).iterations);
+}
+
+#[test]
+fn tictactoe_clock() {
+    let game = TicTacToe::load(&[TL,TM,MM,TR]);
+    let mut mcts = MCTS::new(game);
+    let clock = Clock {
+        remaining: std::time::Duration::from_millis(1000),
+        increment: std::time::Duration::from_millis(0),
+        moves: 3,
+    };
+    let start = std::time::Instant::now();
+    assert!(mcts.ponder_clock(clock) == Some(BR));
+    assert!(start.elapsed() < std::time::Duration::from_millis(1000));
+
+    // there is nothing to search after the game is over
+    let game = TicTacToe::load(&[TL,TM,MM,TR,BR]);
+    let mut mcts = MCTS::new(game);
+    assert!(mcts.ponder_clock(clock).is_none());
+    assert!(mcts.info.n == 0);
+}
+
+#[test]
+fn tictactoe_info() {
+    let game = TicTacToe::new();
+    let mut mcts = MCTS::new(game).with_transposition();
+    mcts.ponder(1000);
+    
+    let info = mcts.info.clone();
+    assert!(info.depth_max <= 9);
+    assert!((info.depth_avg > 0.0) && (info.depth_avg <= info.depth_max as f32));
+    assert!(info.rate > 0.0);
+    assert!(info.branching > 1.0);
+    assert!((info.hit_rate > 0.0) && (info.hit_rate < 1.0));
+    assert!(info.lookups >= info.transpose);
+    
+    // every node below the root is counted once at its depth
+    assert!(info.nodes[0] == 1);
+    assert!(info.nodes[1] == 9);
+    let nodes: u32 = info.nodes.iter().sum();
+    assert!(nodes == info.branch + info.leaf + info.terminal + info.unknown + info.transpose);
+    
+    // playouts are no longer than the number of empty spaces
+    assert!(info.rollouts.len() <= 10);
+    let steps: u64 = info.rollouts.iter().enumerate().map(|(i,&n)| (i as u64)*n).sum();
+    assert!(steps == info.steps);
+    
+    let json = serde_json::to_string(&info).expect("info should serialize");
+    assert!(json.contains("\"nodes\""));
+}
+
+#[test]
+fn tictactoe_observer() {
+    use std::sync::{Arc,Mutex};
+    
+    let snapshots = Arc::new(Mutex::new(Vec::new()));
+    let observed = snapshots.clone();
+    let game = TicTacToe::load(&[TL,TM,MM]);
+    let mut mcts = MCTS::new(game).with_observer(Interval::Iterations(100),move |s: &Snapshot<Grid>| {
+        observed.lock().unwrap().push(s.clone());
+    });
+    mcts.ponder(450);
+    mcts.ponder(550);
+    
+    // the interval carries over between calls to ponder
+    let snapshots = snapshots.lock().unwrap();
+    assert!(snapshots.len() == 10);
+    for (i,s) in snapshots.iter().enumerate() {
+        assert!(s.iterations == 100*(i as u64 + 1));
+        assert!(s.ply.len() == 6);
+        assert!(s.best.is_some());
+    }
+    
+    let last = mcts.snapshot();
+    assert!(last.iterations == 1000);
+    assert!(last.best == Some(BR));
+    assert!(snapshots[9].info.n == last.info.n);
+    
+    // time between calls to ponder is not counted
+    let snapshots = Arc::new(Mutex::new(Vec::new()));
+    let observed = snapshots.clone();
+    let mut mcts = MCTS::new(game).with_observer(Interval::Millis(50),move |s: &Snapshot<Grid>| {
+        observed.lock().unwrap().push(s.elapsed);
+    });
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mcts.ponder(10);
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    mcts.ponder(10);
+    assert!(snapshots.lock().unwrap().is_empty());
+    while snapshots.lock().unwrap().is_empty() {
+        mcts.ponder(100);
+    }
+    assert!(snapshots.lock().unwrap()[0] >= std::time::Duration::from_millis(50));
+}
+
+#[test]
+fn tictactoe_agents() {
+    let game = TicTacToe::new();
+    
+    // perfect play never loses to a random player
+    for seed in 0..10 {
+        let mut random = RandomAgent::new(seed);
+        let mut perfect = AlphaBetaAgent::new(9);
+        let outcome = play(game,&mut random,&mut perfect,&mut |_,_| {});
+        assert!(outcome.result != GameResult::Win);
+        assert!(!outcome.resigned);
+        assert!(outcome.state.gameover().is_some());
+        
+        let outcome = play(game,&mut perfect,&mut random,&mut |_,_| {});
+        assert!(outcome.result != GameResult::Lose);
+    }
+    
+    // perfect play from both sides is a draw
+    let mut mcts = MctsAgent::new(|s| MCTS::new(s).with_transposition(),Budget::Iterations(5000));
+    let mut perfect = AlphaBetaAgent::new(9);
+    let mut replay = game;
+    let outcome = play(game,&mut mcts,&mut perfect,&mut |s,a| {
+        assert!(s.hash() == replay.hash());
+        replay = replay.make(a);
+    });
+    assert!(outcome.result == GameResult::Draw);
+    assert!(outcome.actions.len() == 9);
+    assert!(replay.hash() == outcome.state.hash());
+    assert!(mcts.info.n > 0);
+}
+
+#[test]
+fn tictactoe_human() {
+    let game = TicTacToe::new();
+    let input = "x\n9\n1\nquit\n".as_bytes();
+    let mut output = Vec::new();
+    let mut human = HumanAgent::new(input,&mut output);
+    
+    // the second legal action is chosen after two failed attempts, then the human resigns
+    let mut random = RandomAgent::new(0);
+    let outcome = play(game,&mut human,&mut random,&mut |_,_| {});
+    assert!(outcome.actions[0] == TM);
+    assert!(outcome.actions.len() == 2);
+    assert!(outcome.resigned);
+    assert!(outcome.result == GameResult::Lose);
+    
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("parse failed"));
+    assert!(output.contains("validation failed"));
+    
+    // actions are read and listed in notation, where the middle is the fifth space
+    let input = "x\n0\nactions\n5\nquit\n".as_bytes();
+    let mut output = Vec::new();
+    let mut human = HumanAgent::new(input,&mut output).with_notation();
+    let mut random = RandomAgent::new(0);
+    let outcome = play(game,&mut human,&mut random,&mut |_,_| {});
+    assert!(outcome.actions[0] == MM);
+    assert!(outcome.resigned);
+    
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("illegal action x"));
+    assert!(output.contains("illegal action 0"));
+    assert!(output.contains("1 2 3 4 5 6 7 8 9"));
+}
+
+#[test]
+fn tictactoe_advance() {
+    // O blunders by not blocking the diagonal, and the tree below the blunder is kept
+    let game = TicTacToe::load(&[TL,TM,MM]);
+    let mut mcts = MCTS::new(game).with_transposition();
+    mcts.ponder(5000);
+    assert!(mcts.best() == Some(BR));
+    assert!(mcts.advance(TR));
+    assert!(mcts.info.n > 0);
+    assert!(mcts.best() == Some(BR));
+    mcts.ponder(100);
+    assert!(mcts.best() == Some(BR));
+    
+    // nothing is kept for an action that was never searched
+    let mut mcts = MCTS::new(game);
+    assert!(!mcts.advance(TR));
+    mcts.ponder(100);
+    assert!(mcts.best() == Some(BR));
+    
+    // draws are valued by the side to play at the root
+    let mut mcts = MCTS::new(game).with_contempt(0.2);
+    mcts.ponder(5000);
+    assert!(!mcts.advance(BR));
+    assert!(mcts.info.n == 0);
+    
+    // the tree is kept through every game in self play with transpositions between symmetric game states
+    for seed in 0..10 {
+        let mut random = RandomAgent::new(seed);
+        let mut state = TicTacToe::new();
+        let mut mcts = MCTS::new(state).with_symmetry();
+        let mut kept = 0;
+        while state.gameover().is_none() {
+            mcts.ponder(500);
+            let action = Agent::<Mark,Grid,TicTacToe>::choose(&mut random,&state).unwrap();
+            state = state.make(action);
+            if state.gameover().is_none() && mcts.advance(action) {
+                kept += 1;
+                let nodes: u32 = mcts.info.nodes.iter().sum();
+                assert!(nodes <= mcts.info.branch + mcts.info.leaf + mcts.info.terminal + mcts.info.unknown + mcts.info.transpose);
+            }
+        }
+        assert!(kept > 0);
+    }
+}
+
+#[test]
+fn tictactoe_engine() {
+    let input = "\
+        position 1 2 5 3\n\
+        side\n\
+        go iterations 1000\n\
+        play 9\n\
+        result\n\
+        undo\n\
+        moves\n\
+        play 10\n\
+        quit\n\
+        show\n";
+    let mut output = Vec::new();
+    engine(TicTacToe::new(),MCTS::new,input.as_bytes(),&mut output).unwrap();
+    
+    let output = String::from_utf8(output).unwrap();
+    let mut responses = vec![String::new()];
+    for line in output.lines() {
+        if line.is_empty() {
+            responses.push(String::new());
+        } else {
+            let response = responses.last_mut().unwrap();
+            response.push_str(line);
+            response.push('\n');
+        }
+    }
+    let responses: Vec<&str> = responses.iter().map(|r| r.trim_end()).collect();
+    
+    // one response for each command before quit
+    assert!(responses.len() == 9);
+    assert!(responses[8].is_empty());
+    assert!(responses[0].is_empty());
+    assert!(responses[1] == "p1");
+    assert!(responses[2].ends_with("bestmove 9"));
+    assert!(responses[4] == "p1");
+    assert!(responses[6] == "1 2 5 3");
+    assert!(responses[7] == "error illegal action 10");
+}
+
+#[test]
+fn tictactoe_notation() {
+    let game = TicTacToe::new();
+    assert!(game.legal() == ["1","2","3","4","5","6","7","8","9"]);
+    assert!(game.parse("5") == Some(MM));
+    assert!(game.parse("0").is_none());
+    assert!(game.parse("x").is_none());
+
+    let game = game.make(MM);
+    assert!(game.parse("5").is_none());
+    game.actions(&mut |a| assert!(game.parse(&game.format(a)) == Some(a)));
+}
+
+#[test]
+fn tictactoe_position() {
+    let game = TicTacToe::load(&[TL,MM,BR]);
+    assert!(game.position() == "X---O---X");
+    let copy = TicTacToe::from_position("X---O---X").expect("should read the position");
+    assert!(copy.space == game.space && copy.side == Mark::O && copy.hash == game.hash);
+
+    let game = TicTacToe::load(&[TL,TM,MM,TR,BR]);
+    let copy = TicTacToe::from_position(&game.position()).unwrap();
+    assert!(copy.gameover() == Some(GameResult::Lose));
+    assert!(copy.hash == game.hash);
+
+    // every game state of a game has the same hashes when it is read from its position
+    let mut game = TicTacToe::new();
+    for &m in [MM,TL,BR,TR,BL,ML,TM].iter() {
+        game = game.make(m);
+        let copy = TicTacToe::from_position(&game.position()).unwrap();
+        assert!(copy == game);
+        assert!(copy.hash() == game.hash());
+        assert!(copy.canonical() == game.canonical());
+    }
+
+    assert!(TicTacToe::from_position("").is_err());
+    assert!(TicTacToe::from_position("X---O---").is_err());
+    assert!(TicTacToe::from_position("X---A---X").is_err());
+    assert!(TicTacToe::from_position("XX-------").is_err());
+    assert!(TicTacToe::from_position("XXXOO-O--").is_err());
+    // both sides have three in a row
+    assert!(TicTacToe::from_position("OOOXXX---").is_err());
+    assert!(TicTacToe::from_position("XXXOOO-X-").is_err());
+    // O played on after X won
+    assert!(TicTacToe::from_position("XXXOOXO-O").is_err());
+    // the last mark may complete two lines at once
+    assert!(TicTacToe::from_position("XXXOXOOOX").is_ok());
+}
+
+
+#[test]
+fn tictactoe_record() {
+    let start = TicTacToe::new();
+    let p1 = Seat::new("x",serde_json::json!({"type": "random"}));
+    let p2 = Seat::new("o",serde_json::json!({"type": "mcts", "iterations": 100}));
+    let record = Record::new("tictactoe",p1,p2).with_actions(&start,&[TL,MM,TM,BR,TR],false);
+    assert!(record.moves == ["1","5","2","9","3"]);
+    assert!(record.result == "p1");
+    assert!(record.date.len() == 10);
+
+    // records are written and read back as lines of json
+    let mut output = Vec::new();
+    record.write(&mut output).unwrap();
+    record.write(&mut output).unwrap();
+    let records = read_records(output.as_slice()).unwrap();
+    assert!(records.len() == 2);
+    assert!(records[0] == record);
+    assert!(record.replay(start).is_ok());
+
+    let resigned = Record::new("tictactoe",Seat::default(),Seat::default()).with_actions(&start,&[TL],true);
+    assert!(resigned.result == "p1" && resigned.resigned);
+    assert!(resigned.replay(start).is_ok());
+
+    // records that do not match a game are rejected
+    let mut bad = record.clone();
+    bad.result = String::from("p2");
+    assert!(bad.replay(start).is_err());
+    bad.result = String::from("p1");
+    bad.moves[1] = String::from("1");
+    assert!(bad.replay(start).is_err());
+    bad.moves = vec![String::from("1"),String::from("5"),String::from("2"),String::from("9"),String::from("3"),String::from("4")];
+    assert!(bad.replay(start).is_err());
+    assert!(read_records("{\"moves\": 3}".as_bytes()).is_err());
+}
+
+#[test]
+fn tictactoe_perft() {
+    // every game is counted once because a game over counts as a leaf
+    let game = TicTacToe::new();
+    assert!(perft(&game,9,false) == 255168);
+    assert!(perft(&game,9,true) == 255168);
+    assert!(perft(&game,1,false) == 9);
+    assert!(perft(&game,0,false) == 1);
+
+    let split = divide(&game,9,false);
+    assert!(split.len() == 9);
+    assert!(split.iter().map(|&(_,n)| n).sum::<u64>() == 255168);
+    assert!(split[0].1 == split[2].1 && split[0].1 != split[4].1);
+
+    let game = TicTacToe::load(&[TL,TM,MM,TR,BR]);
+    assert!(perft(&game,3,false) == 1);
+    assert!(divide(&game,3,false).is_empty());
+}
+
+// tic tac toe with a bug after the bottom right space is taken
+#[derive(Clone,Debug,PartialEq)]
+struct Broken(TicTacToe,bool);
+
+impl std::fmt::Display for Broken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,"{}",self.0)
+    }
+}
+
+impl GameState<Mark,Grid> for Broken {
+    fn actions<F>(&self, f: &mut F) where F: FnMut(Grid) {
+        // no actions in some game states that are not over
+        if !self.1 && (self.0.space[BR as usize] != Mark::N) {
+            return;
+        }
+        self.0.actions(f);
+    }
+
+    fn make(&self, action: Grid) -> Self {
+        // panics in the middle of the game
+        assert!(!self.1 || (action != BR) || (self.0.space[MM as usize] == Mark::N),"bottom right after the middle");
+        Broken(self.0.make(action),self.1)
+    }
+
+    fn gameover(&self) -> Option<GameResult> {self.0.gameover()}
+    fn player(&self) -> Mark {self.0.player()}
+    fn hash(&self) -> u64 {self.0.hash()}
+}
+
+// tic tac toe with a hash that depends on the order of the actions, or with results from the perspective of the side that made the final action
+#[derive(Clone,Debug)]
+struct Mistaken(TicTacToe,u64,bool);
+
+impl PartialEq for Mistaken {
+    fn eq(&self, other: &Mistaken) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl std::fmt::Display for Mistaken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f,"{}",self.0)
+    }
+}
+
+impl GameState<Mark,Grid> for Mistaken {
+    fn actions<F>(&self, f: &mut F) where F: FnMut(Grid) {self.0.actions(f)}
+    fn player(&self) -> Mark {self.0.player()}
+
+    fn make(&self, action: Grid) -> Self {
+        Mistaken(self.0.make(action),self.1.wrapping_mul(31).wrapping_add(action as u64 + 1),self.2)
+    }
+
+    fn gameover(&self) -> Option<GameResult> {
+        match self.0.gameover() {
+            Some(GameResult::Win) if self.2 => Some(GameResult::Lose),
+            Some(GameResult::Lose) if self.2 => Some(GameResult::Win),
+            result => result,
+        }
+    }
+
+    fn hash(&self) -> u64 {
+        if self.2 {self.0.hash()} else {self.1}
+    }
+}
+
+#[test]
+fn tictactoe_conformance() {
+    if let Err(violation) = conformance(&TicTacToe::new(),1000,1) {
+        panic!("{}",violation);
+    }
+
+    // the failing sequences are shrunk to the action that shows the bug
+    let violation = conformance(&Broken(TicTacToe::new(),false),100,1).unwrap_err();
+    assert!(violation.actions.len() == 1);
+    assert!(violation.actions[0] == BR);
+    assert!(violation.message == "no actions before the game is over");
+
+    let violation = conformance(&Broken(TicTacToe::new(),true),100,1).unwrap_err();
+    assert!(violation.actions.len() == 1);
+    assert!(violation.actions[0] == MM);
+    assert!(violation.message.starts_with("panicked"));
+
+    // equal game states are found when they are reached in a different order
+    let violation = conformance(&Mistaken(TicTacToe::new(),0,false),100,1).unwrap_err();
+    assert!(violation.message.starts_with("hash"));
+
+    // the last action wins every decisive game of tic tac toe, so the side to play cannot
+    let violation = conformance(&Mistaken(TicTacToe::new(),0,true),100,1).unwrap_err();
+    assert!(violation.message.starts_with("the side to play wins"));
+    let game = TicTacToe::load(&violation.actions);
+    assert!(game.gameover() == Some(GameResult::Lose));
+}
+
+// values of the actions in the first ply
+fn ply_values<S: GameState<Mark,Grid>>(mcts: &MCTS<Mark,Grid,S>) -> Vec<(Grid,f32)> {
+    let mut values = Vec::new();
+    mcts.ply(&mut |(a,w,_,_)| values.push((a,w)));
+    values
+}
+
+// the searches agree on the value of the best action and the best actions are the same up to symmetry
+fn agree<S: GameState<Mark,Grid>>(moves: &[Grid], expected: &MCTS<Mark,Grid,TicTacToe>, mcts: &MCTS<Mark,Grid,S>) {
+    let max = |values: Vec<(Grid,f32)>| values.iter().fold(0.0f32,|max,&(_,w)| max.max(w));
+    let x = max(ply_values(expected));
+    let y = max(ply_values(mcts));
+    assert!((x - y).abs() < 0.05,"{:?} best values {} and {} differ",moves,x,y);
+
+    let game = TicTacToe::load(moves);
+    let a = game.make(expected.best().unwrap()).canonical().0;
+    let b = game.make(mcts.best().unwrap()).canonical().0;
+    assert!(a == b,"{:?} best actions {:?} and {:?} differ",moves,expected.best(),mcts.best());
+}
+
+#[test]
+fn tictactoe_transpose_root() {
+    // the actions at the root are transpositions to game states that were first reached below other actions
+    for moves in [&[TM,MM,BM][..],&[ML,MM,MR,TM],&[TL,MM,BR,TR,BL]] {
+        let mut plain = MCTS::new(TicTacToe::load(moves));
+        plain.ponder(20000);
+        let mut mcts = MCTS::new(TicTacToe::new()).with_transposition();
+        mcts.ponder(50000);
+        for &m in moves {
+            mcts.advance(m);
+        }
+        mcts.ponder(20000);
+        agree(moves,&plain,&mcts);
+    }
+}
+
+#[test]
+fn tictactoe_advance_tables() {
+    // the transposition map and the MAST averages are kept for the side to play after each action
+    for moves in [&[MM][..],&[TL,MM,BR],&[TM,MM,BM,ML,MR]] {
+        let mut plain = MCTS::new(TicTacToe::load(moves)).with_rollout_policy(RolloutPolicy::Mast(0.1));
+        plain.ponder(20000);
+        let mut mcts = MCTS::new(TicTacToe::new()).with_transposition().with_rollout_policy(RolloutPolicy::Mast(0.1));
+        mcts.ponder(20000);
+        for &m in moves {
+            let before: Vec<_> = [Mark::X,Mark::O].iter().map(|&p| mcts.mast(p,m)).collect();
+            mcts.advance(m);
+            let after: Vec<_> = [Mark::X,Mark::O].iter().map(|&p| mcts.mast(p,m)).collect();
+            assert!(before == after && before[0] != before[1]);
+            let info = &mcts.info;
+            assert!(info.collisions == 0);
+            assert!(info.lookups < info.branch + info.leaf + info.terminal + info.transpose);
+            mcts.ponder(5000);
+            assert!(mcts.info.hit_rate <= 1.0);
+        }
+        mcts.ponder(20000);
+        agree(moves,&plain,&mcts);
+    }
+
+    // nothing is kept when the search starts over
+    let mut mcts = MCTS::new(TicTacToe::new()).with_transposition().with_rollout_policy(RolloutPolicy::Mast(0.1)).with_contempt(0.2);
+    mcts.ponder(5000);
+    assert!(mcts.mast(Mark::X,MM).is_some());
+    assert!(!mcts.advance(MM));
+    assert!(mcts.mast(Mark::X,MM).is_none() && mcts.mast(Mark::O,MM).is_none());
+    assert!(mcts.info.lookups == 0 && mcts.info.hit_rate == 0.0);
+}
+
+
+#[test]
+fn tictactoe_symmetry_ply() {
+    // symmetric actions at the root are transpositions to the same canonical game state
+    for moves in [&[TM,MM,BM][..],&[ML,MM,MR,TM],&[TL,MM,BR,TR,BL]] {
+        let mut plain = MCTS::new(TicTacToe::load(moves));
+        plain.ponder(20000);
+        let mut mcts = MCTS::new(TicTacToe::load(moves)).with_symmetry();
+        mcts.ponder(20000);
+        agree(moves,&plain,&mcts);
+    }
+}
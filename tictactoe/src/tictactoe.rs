@@ -30,6 +30,25 @@ pub static ALLMOVES: [Grid;9] = [
     BL,BM,BR
 ];
 
+// the spaces of each line of three
+static LINES: [(usize,usize,usize);8] = [
+    (0,1,2),(3,4,5),(6,7,8),
+    (0,3,6),(1,4,7),(2,5,8),
+    (0,4,8),(2,4,6)
+];
+
+// SYMMETRY[t][i] is the space that space i is moved to by rotation or reflection t
+static SYMMETRY: [[usize;9];8] = [
+    [0,1,2,3,4,5,6,7,8],
+    [2,5,8,1,4,7,0,3,6],
+    [8,7,6,5,4,3,2,1,0],
+    [6,3,0,7,4,1,8,5,2],
+    [2,1,0,5,4,3,8,7,6],
+    [6,7,8,3,4,5,0,1,2],
+    [0,3,6,1,4,7,2,5,8],
+    [8,5,2,7,4,1,6,3,0],
+];
+
 #[derive(Copy,Clone,Debug)]
 pub struct TicTacToe {
     pub space: [Mark;9],
@@ -86,20 +105,13 @@ impl TicTacToe {
     pub fn load(moves: &[Grid]) -> TicTacToe {
         let mut b = TicTacToe::new();
         for m in moves {
-            println!("{}",b);
             b = b.make(*m);
         }
         b
     }
 
     fn winner(&self) -> Mark {
-        let lines = [
-            (0,1,2),(3,4,5),(6,7,8),
-            (0,3,6),(1,4,7),(2,5,8),
-            (0,4,8),(2,4,6)
-        ];
-
-        for (i,j,k) in lines.iter() {
+        for (i,j,k) in LINES.iter() {
             let a = self.space[*i];
             let b = self.space[*j];
             let c = self.space[*k];
@@ -112,16 +124,26 @@ impl TicTacToe {
         
         Mark::N
     }
+
+    // number of lines of three of the mark
+    fn lines(&self, mark: Mark) -> usize {
+        LINES.iter().filter(|&&(i,j,k)| (self.space[i] == mark) && (self.space[j] == mark) && (self.space[k] == mark)).count()
+    }
 }
 
+// equal game states have the same marks and side to play, whatever their hashes are
 impl PartialEq for TicTacToe {
     fn eq(&self, other: &TicTacToe) -> bool {
-        other.hash() == self.hash()
+        (self.space == other.space) && (self.side == other.side)
     }
 }
 
 
-impl Action for Grid {}
+impl Action for Grid {
+    fn key(&self) -> Option<u64> {
+        Some(*self as u64)
+    }
+}
 impl Player for Mark {}
 
 impl GameState<Mark,Grid> for TicTacToe {
@@ -157,6 +179,34 @@ impl GameState<Mark,Grid> for TicTacToe {
         self.hash
     }
 
+    fn canonical(&self) -> (u64,u8) {
+        let mut best = (self.hash,0);
+        for (t,sym) in SYMMETRY.iter().enumerate().skip(1) {
+            let mut hash = 0;
+            for (i,mark) in self.space.iter().enumerate() {
+                match mark {
+                    Mark::X => hash |= 1 << sym[i],
+                    Mark::O => hash |= 512 << sym[i],
+                    Mark::N => {}
+                }
+            }
+            if hash < best.0 {
+                best = (hash,t as u8);
+            }
+        }
+        best
+    }
+
+    fn transform(&self, action: Grid, symmetry: u8) -> Grid {
+        ALLMOVES[SYMMETRY[symmetry as usize][action as usize]]
+    }
+
+    fn inverse(&self, action: Grid, symmetry: u8) -> Grid {
+        let sym = &SYMMETRY[symmetry as usize];
+        let i = sym.iter().position(|&j| j == action as usize).expect("symmetry should be a permutation");
+        ALLMOVES[i]
+    }
+
     fn gameover(&self) -> Option<GameResult> {
         let winner = self.winner();
         if (self.turn == 9) || (winner != Mark::N) {
@@ -175,3 +225,65 @@ impl GameState<Mark,Grid> for TicTacToe {
         self.side
     }
 }
+
+// spaces are numbered 1 to 9 from the top left like the board display
+impl Notation<Mark,Grid> for TicTacToe {
+    fn parse(&self, text: &str) -> Option<Grid> {
+        let p = text.trim().parse::<usize>().ok()?;
+        if (1..=9).contains(&p) && (self.space[p-1] == Mark::N) && self.gameover().is_none() {
+            Some(ALLMOVES[p-1])
+        } else {
+            None
+        }
+    }
+
+    fn format(&self, action: Grid) -> String {
+        (action as usize + 1).to_string()
+    }
+}
+
+// the spaces from the top left as "-", "X", or "O" and the side to play follows from the number of marks because X plays first
+// e.g. "X---O---X"
+impl Position<Mark,Grid> for TicTacToe {
+    fn position(&self) -> String {
+        self.space.iter().map(|&m| if m == Mark::N {'-'} else if m == Mark::X {'X'} else {'O'}).collect()
+    }
+
+    fn from_position(text: &str) -> Result<Self,String> {
+        let text = text.trim();
+        if text.chars().count() != 9 {
+            return Err(format!("expected 9 spaces in {}",text));
+        }
+
+        let mut game = TicTacToe::new();
+        let mut marks = (0,0);
+        for (i,c) in text.chars().enumerate() {
+            match c {
+                'X' => {
+                    game.space[i] = Mark::X;
+                    game.hash |= 1 << i;
+                    marks.0 += 1;
+                },
+                'O' => {
+                    game.space[i] = Mark::O;
+                    game.hash |= 512 << i;
+                    marks.1 += 1;
+                },
+                '-' => {},
+                _ => return Err(format!("expected -, X, or O but found {}",c)),
+            }
+        }
+
+        game.turn = marks.0 + marks.1;
+        game.side = match marks.0 as i32 - marks.1 as i32 {
+            0 => Mark::X,
+            1 => Mark::O,
+            _ => return Err(format!("expected X to play first but found {} X and {} O",marks.0,marks.1)),
+        };
+        // only the side that played last can have three in a row, otherwise both sides have one or the game went on after it was won
+        if game.lines(game.side) > 0 {
+            return Err(format!("found three in a row for the side to play in {}",text));
+        }
+        Ok(game)
+    }
+}
@@ -49,4 +49,7 @@ impl GameInstance<Disc,Column> for Connect4 {
         }
     }
 
+    fn configure(mcts: MCTS<Disc,Column,Self>) -> MCTS<Disc,Column,Self> {
+        mcts.with_verified_transposition()
+    }
 }
\ No newline at end of file
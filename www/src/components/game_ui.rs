@@ -15,6 +15,9 @@ pub trait GameInstance<P: GIPlayer, A: GIAction>: GameState<P,A> + Copy + 'stati
     fn name() -> &'static str;
     fn status(&self) -> String;
     fn view(&self, make: yew::Callback<A>, actions: Vec<(A,&'static str)>) -> Html;
+    fn configure(mcts: MCTS<P,A,Self>) -> MCTS<P,A,Self> {
+        mcts
+    }
 }
 
 pub struct GameUI<P: GIPlayer, A: GIAction, I: GameInstance<P,A>> {
@@ -26,6 +29,7 @@ pub struct GameUI<P: GIPlayer, A: GIAction, I: GameInstance<P,A>> {
     ai_duration: u64,
     ai_progress: u64,
     ai_eve: u64,
+    ai_level: u64,
     weighted_actions: Vec<(A,f32)>,
     actions: Vec<(A,&'static str)>,
 }
@@ -46,38 +50,49 @@ impl<P: GIPlayer, A: GIAction, I: GameInstance<P,A>> GameUI<P,A,I> {
             ai_duration: 1,
             ai_progress: 0,
             ai_eve: 28,
+            ai_level: Strength::MAX_LEVEL as u64,
             weighted_actions: Vec::new(),
             actions: actions,
         }
     }
 
     fn ponder(&mut self, ms: u32) {
+        let budget = self.strength().iterations;
         if let Some(mcts) = &mut self.mcts {
             let us = ms * 1000;
             let ns = us * 1000;
             let duration = Duration::new(0, ns);
             let start = Instant::now();
 
-            while (Instant::now() - start) < duration {
+            while ((Instant::now() - start) < duration) && ((mcts.info.n as usize) < budget) {
                 mcts.ponder(100);
             }
 
             self.actions.clear();
-            mcts.ply(&mut |(a,w,_s)| 
+            mcts.ply(&mut |(a,w,_s,_m)| 
                 self.weighted_actions.push((a,w))
             );
             colorize(&self.weighted_actions, &mut self.actions);
-            self.info = Some(mcts.info);
+            self.info = Some(mcts.info.clone());
 
         } else {
-            self.mcts = Some(
+            self.mcts = Some(I::configure(
                 MCTS::new(self.instance)
                 .with_exploration((self.ai_eve as f32)/20.0)
-            );
+            ));
             self.ponder(ms);
         }
     }
     
+    // the strongest level searches for the whole time budget, however many iterations that is
+    fn strength(&self) -> Strength {
+        if self.ai_level >= Strength::MAX_LEVEL as u64 {
+            Strength {temperature: 0.0, iterations: usize::MAX}
+        } else {
+            Strength::level(self.ai_level as u8)
+        }
+    }
+    
     fn trigger_ai(&self, ctx: &Context<Self>) {
         // one half duty cycle with 50 ms period
         let ms = 50;
@@ -90,6 +105,7 @@ impl<P: GIPlayer, A: GIAction, I: GameInstance<P,A>> GameUI<P,A,I> {
 
 pub enum Msg<A: GIAction> {
     SetAiEve(u64),
+    SetAiLevel(u64),
     SetAiTime(u64),
     Ponder(u32),
     Make(A),
@@ -105,6 +121,10 @@ fn fmt_ai_eve(n: u64) -> String {
     format!("{:0.2}",(n as f32) / 20.0)
 }
 
+fn fmt_ai_level(n: u64) -> String {
+    format!("{}",n)
+}
+
 impl<P: GIPlayer, A: GIAction, I: GameInstance<P,A>> Component for GameUI<P,A,I> {
     type Properties = ();
     type Message = Msg<A>;
@@ -120,9 +140,11 @@ impl<P: GIPlayer, A: GIAction, I: GameInstance<P,A>> Component for GameUI<P,A,I>
             Msg::Reset => {
                 let ai_time = self.ai_duration;
                 let ai_eve  = self.ai_eve;
+                let ai_level = self.ai_level;
                 *self = GameUI::reset();
                 self.ai_duration = ai_time;
                 self.ai_eve = ai_eve;
+                self.ai_level = ai_level;
                 true
             },
 
@@ -157,19 +179,13 @@ impl<P: GIPlayer, A: GIAction, I: GameInstance<P,A>> Component for GameUI<P,A,I>
                 self.ponder(ms);
                 let elapsed = (Instant::now() - self.ai_start).as_millis() as u64;
                 self.ai_progress = elapsed / (10*self.ai_duration);
-                if self.ai_progress < 100 {
+                let strength = self.strength();
+                let spent = self.mcts.as_ref().is_some_and(|mcts| (mcts.info.n as usize) >= strength.iterations);
+                if (self.ai_progress < 100) && !spent {
                     self.trigger_ai(ctx);
                 } else {
-                    let mut best = None;
-                    let mut max_w = -0.1;
-                    for (a,w) in &self.weighted_actions {
-                        let weight = *w;
-                        if max_w < weight {
-                            max_w = weight;
-                            best = Some(*a);
-                        }
-                    }
-                    let action = best.expect("Should find best action");
+                    let mcts = self.mcts.as_mut().expect("Should have searched");
+                    let action = mcts.sample(strength.temperature).expect("Should find best action");
                     ctx.link().send_message(Msg::Make(action));
                 }
                 true
@@ -183,6 +199,11 @@ impl<P: GIPlayer, A: GIAction, I: GameInstance<P,A>> Component for GameUI<P,A,I>
             Msg::SetAiEve(n) => {
                 self.ai_eve = n;
                 true
+            },
+
+            Msg::SetAiLevel(n) => {
+                self.ai_level = n;
+                true
             }
         }
     }
@@ -197,6 +218,7 @@ impl<P: GIPlayer, A: GIAction, I: GameInstance<P,A>> Component for GameUI<P,A,I>
         let info = html_info(&self.info);
         let set_ai_time = ctx.link().callback(|u| Msg::SetAiTime(u));
         let set_ai_eve = ctx.link().callback(|u| Msg::SetAiEve(u));
+        let set_ai_level = ctx.link().callback(|u| Msg::SetAiLevel(u));
 
         html! {
             <div class="game-layout">
@@ -240,6 +262,15 @@ impl<P: GIPlayer, A: GIAction, I: GameInstance<P,A>> Component for GameUI<P,A,I>
                         input={set_ai_eve}
                         fmt={SettingFormat::set(fmt_ai_eve)}
                     />
+                    <Setting
+                        class={"ai-level"}
+                        min={1}
+                        max={Strength::MAX_LEVEL as u64}
+                        default={self.ai_level}
+                        name={"Difficulty"}
+                        input={set_ai_level}
+                        fmt={SettingFormat::set(fmt_ai_level)}
+                    />
                 </div>
                 
                 {self.instance.view(make, self.actions.clone())}
@@ -47,6 +47,26 @@ pub fn html_info(info: &Option<Info>) -> Html {
                     }
                 }</div>
                 
+                <div>{"Iterations/s:"}</div>
+                <div>{
+                    if i.rate > m as f32 {
+                        format!("{:.0}M",i.rate / m as f32)
+                    } else if i.rate > k as f32 {
+                        format!("{:.0}K",i.rate / k as f32)
+                    } else {
+                        format!("{:.0}",i.rate)
+                    }
+                }</div>
+                
+                <div>{"Depth:"}</div>
+                <div>{format!("{:.1}/{}",i.depth_avg,i.depth_max)}</div>
+                
+                <div>{"Branching:"}</div>
+                <div>{format!("{:.1}",i.branching)}</div>
+                
+                <div>{"Transpositions:"}</div>
+                <div>{format!("{:.0}%",i.hit_rate * 100.0)}</div>
+                
                 <div>{"Leaf Nodes:"}</div>
                 <div>{
                     if (i.leaf + i.unknown) > m {
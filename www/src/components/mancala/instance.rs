@@ -48,4 +48,7 @@ impl GameInstance<Side,Pit> for Mancala {
         }
     }
 
+    fn configure(mcts: MCTS<Side,Pit,Self>) -> MCTS<Side,Pit,Self> {
+        mcts.with_verified_transposition()
+    }
 }
\ No newline at end of file
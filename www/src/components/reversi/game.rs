@@ -46,7 +46,7 @@ impl Game {
             }
 
             self.actions.clear();
-            mcts.ply(&mut |(a,w,_s)| self.weighted_actions.push((a,w)));
+            mcts.ply(&mut |(a,w,_s,_m)| self.weighted_actions.push((a,w)));
             colorize(&self.weighted_actions, &mut self.actions);
             self.info = Some(mcts.info);
 
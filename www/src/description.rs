@@ -56,6 +56,12 @@ pub fn description() -> Html {
                         </a>
                         {" for more explanation."
                     }</li>
+                    <li>{
+                        "Difficulty: Adjust the playing strength of the AI 
+                        agent. Lower levels search fewer positions and choose 
+                        randomly between the actions they searched the most, 
+                        which gives weaker and more varied play."
+                    }</li>
                     <li>{
                         "AI Advantage: The AI agent's own estimate of the
                         playing strength of it's position or how probable it 
@@ -73,6 +79,22 @@ pub fn description() -> Html {
                         "Iterations: The number of complete steps of the MCTS 
                         algorithm."
                     }</li>
+                    <li>{
+                        "Iterations/s: The number of complete steps of the MCTS 
+                        algorithm per second."
+                    }</li>
+                    <li>{
+                        "Depth: The average and deepest number of moves the 
+                        search looked ahead."
+                    }</li>
+                    <li>{
+                        "Branching: The effective number of actions searched 
+                        at each move."
+                    }</li>
+                    <li>{
+                        "Transpositions: How often a position was found that 
+                        was already in the search tree."
+                    }</li>
                     <li>{
                         "Branch/Leaf Nodes: Provides an idea of the shape of 
                         search tree graph. Branch nodes are interior and leaf 